@@ -0,0 +1,5 @@
+-- Migration 007: Support aggregated episode action queries
+-- Covers the "latest action per episode" lookup used by `aggregated=true`
+
+CREATE INDEX IF NOT EXISTS idx_episode_actions_latest
+ON episode_actions(user_id, episode_url, timestamp DESC, id DESC);
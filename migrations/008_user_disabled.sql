@@ -0,0 +1,4 @@
+-- Migration 008: Allow administrators to disable user accounts
+-- Disabled users keep their data but can no longer authenticate
+
+ALTER TABLE users ADD COLUMN disabled BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- Migration 009: Conditional request state of the background feed fetcher
+
+ALTER TABLE podcasts ADD COLUMN etag TEXT;
+ALTER TABLE podcasts ADD COLUMN last_modified TEXT;
+ALTER TABLE podcasts ADD COLUMN last_fetched_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_podcasts_last_fetched ON podcasts(last_fetched_at);
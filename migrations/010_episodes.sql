@@ -0,0 +1,20 @@
+-- Migration 010: Episode catalogue populated from parsed feeds
+
+CREATE TABLE IF NOT EXISTS episodes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    podcast_id INTEGER NOT NULL REFERENCES podcasts(id) ON DELETE CASCADE,
+    guid TEXT NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT,
+    description TEXT,
+    website TEXT,
+    duration INTEGER,
+    released INTEGER,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE(podcast_id, guid)
+);
+
+-- Clients identify episodes by their media URL
+CREATE INDEX IF NOT EXISTS idx_episodes_url ON episodes(url);
+CREATE INDEX IF NOT EXISTS idx_episodes_podcast_released ON episodes(podcast_id, released DESC);
@@ -0,0 +1,9 @@
+-- Migration 011: Directory tags taken from the categories of parsed feeds
+
+CREATE TABLE IF NOT EXISTS podcast_tags (
+    podcast_id INTEGER NOT NULL REFERENCES podcasts(id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (podcast_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_podcast_tags_tag ON podcast_tags(tag);
@@ -0,0 +1,59 @@
+-- Migration 012: Full-text search indexes over podcasts and episodes.
+-- Both are external content tables kept in sync by triggers, so only the
+-- index itself is stored twice.
+
+CREATE VIRTUAL TABLE IF NOT EXISTS podcasts_fts USING fts5(
+    title,
+    description,
+    url,
+    content = 'podcasts',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS podcasts_fts_insert AFTER INSERT ON podcasts BEGIN
+    INSERT INTO podcasts_fts (rowid, title, description, url)
+    VALUES (new.id, new.title, new.description, new.url);
+END;
+
+CREATE TRIGGER IF NOT EXISTS podcasts_fts_delete AFTER DELETE ON podcasts BEGIN
+    INSERT INTO podcasts_fts (podcasts_fts, rowid, title, description, url)
+    VALUES ('delete', old.id, old.title, old.description, old.url);
+END;
+
+-- Subscriber counts and fetch state change far more often than the text
+CREATE TRIGGER IF NOT EXISTS podcasts_fts_update AFTER UPDATE OF title, description, url ON podcasts BEGIN
+    INSERT INTO podcasts_fts (podcasts_fts, rowid, title, description, url)
+    VALUES ('delete', old.id, old.title, old.description, old.url);
+    INSERT INTO podcasts_fts (rowid, title, description, url)
+    VALUES (new.id, new.title, new.description, new.url);
+END;
+
+CREATE VIRTUAL TABLE IF NOT EXISTS episodes_fts USING fts5(
+    title,
+    description,
+    content = 'episodes',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS episodes_fts_insert AFTER INSERT ON episodes BEGIN
+    INSERT INTO episodes_fts (rowid, title, description)
+    VALUES (new.id, new.title, new.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS episodes_fts_delete AFTER DELETE ON episodes BEGIN
+    INSERT INTO episodes_fts (episodes_fts, rowid, title, description)
+    VALUES ('delete', old.id, old.title, old.description);
+END;
+
+CREATE TRIGGER IF NOT EXISTS episodes_fts_update AFTER UPDATE OF title, description ON episodes BEGIN
+    INSERT INTO episodes_fts (episodes_fts, rowid, title, description)
+    VALUES ('delete', old.id, old.title, old.description);
+    INSERT INTO episodes_fts (rowid, title, description)
+    VALUES (new.id, new.title, new.description);
+END;
+
+-- Index rows that existed before this migration
+INSERT INTO podcasts_fts (podcasts_fts) VALUES ('rebuild');
+INSERT INTO episodes_fts (episodes_fts) VALUES ('rebuild');
@@ -0,0 +1,10 @@
+-- Migration 013: Cached co-subscription counts used for suggestions.
+-- Rebuilt periodically from `subscriptions`; `score` is the number of users
+-- subscribed to both podcasts.
+
+CREATE TABLE IF NOT EXISTS podcast_similarity (
+    podcast_url TEXT NOT NULL,
+    similar_url TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    PRIMARY KEY (podcast_url, similar_url)
+);
@@ -0,0 +1,3 @@
+-- Migration 014: Episode GUID of episode actions, as sent by gpoddersync clients
+
+ALTER TABLE episode_actions ADD COLUMN guid TEXT;
@@ -0,0 +1,13 @@
+-- Migration 015: Make episode action uploads idempotent.
+-- Retried uploads used to store the same action several times. Keep the most
+-- recently stored copy of each action, then enforce the natural key.
+
+DELETE FROM episode_actions
+WHERE id NOT IN (
+    SELECT MAX(id)
+    FROM episode_actions
+    GROUP BY user_id, device_id, episode_url, action, timestamp, COALESCE(position, -1)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_episode_actions_natural_key
+ON episode_actions(user_id, device_id, episode_url, action, timestamp, COALESCE(position, -1));
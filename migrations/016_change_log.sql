@@ -0,0 +1,88 @@
+-- Migration 016: Monotonic change sequence used as sync cursor.
+-- Every change to subscriptions, episode actions and the episode catalogue
+-- gets an entry here; its id is stamped on the changed row as `change_seq`
+-- and handed out to clients instead of the wall-clock time.
+
+CREATE TABLE IF NOT EXISTS change_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- NULL for changes of the shared episode catalogue
+    user_id INTEGER REFERENCES users(id) ON DELETE CASCADE,
+    entity TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+-- Clients still hold Unix timestamps handed out before this migration. Starting
+-- the sequence at the current time keeps every new cursor above them, so those
+-- clients receive all changes once instead of none.
+INSERT INTO sqlite_sequence (name, seq)
+SELECT 'change_log', strftime('%s', 'now')
+WHERE NOT EXISTS (SELECT 1 FROM sqlite_sequence WHERE name = 'change_log');
+
+ALTER TABLE subscriptions ADD COLUMN change_seq INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE episode_actions ADD COLUMN change_seq INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE episodes ADD COLUMN change_seq INTEGER NOT NULL DEFAULT 0;
+
+-- Backfill existing rows in the order they were originally written
+INSERT INTO change_log (user_id, entity, entity_id)
+SELECT user_id, 'subscription', id FROM subscriptions
+ORDER BY MAX(added_at, COALESCE(removed_at, 0)), id;
+
+INSERT INTO change_log (user_id, entity, entity_id)
+SELECT user_id, 'episode_action', id FROM episode_actions
+ORDER BY id;
+
+INSERT INTO change_log (user_id, entity, entity_id)
+SELECT NULL, 'episode', id FROM episodes
+ORDER BY id;
+
+UPDATE subscriptions SET change_seq = (
+    SELECT MAX(c.id) FROM change_log c
+    WHERE c.entity = 'subscription' AND c.entity_id = subscriptions.id
+);
+
+UPDATE episode_actions SET change_seq = (
+    SELECT MAX(c.id) FROM change_log c
+    WHERE c.entity = 'episode_action' AND c.entity_id = episode_actions.id
+);
+
+UPDATE episodes SET change_seq = (
+    SELECT MAX(c.id) FROM change_log c
+    WHERE c.entity = 'episode' AND c.entity_id = episodes.id
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_change_seq ON subscriptions(user_id, change_seq);
+CREATE INDEX IF NOT EXISTS idx_episode_actions_change_seq ON episode_actions(user_id, change_seq);
+CREATE INDEX IF NOT EXISTS idx_episodes_change_seq ON episodes(change_seq);
+
+-- The triggers only watch the payload columns, so stamping `change_seq`
+-- does not log another change
+CREATE TRIGGER IF NOT EXISTS subscriptions_change_insert AFTER INSERT ON subscriptions BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'subscription', new.id);
+    UPDATE subscriptions SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS subscriptions_change_update AFTER UPDATE OF removed_at, added_at ON subscriptions BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'subscription', new.id);
+    UPDATE subscriptions SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS episode_actions_change_insert AFTER INSERT ON episode_actions BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'episode_action', new.id);
+    UPDATE episode_actions SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
+
+-- Retried uploads of an unchanged action are not broadcast again
+CREATE TRIGGER IF NOT EXISTS episode_actions_change_update AFTER UPDATE OF podcast_url, guid, started, total ON episode_actions
+WHEN old.podcast_url IS NOT new.podcast_url OR old.guid IS NOT new.guid
+    OR old.started IS NOT new.started OR old.total IS NOT new.total
+BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'episode_action', new.id);
+    UPDATE episode_actions SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
+
+-- Catalogue updates only matter to clients when an episode first appears
+CREATE TRIGGER IF NOT EXISTS episodes_change_insert AFTER INSERT ON episodes BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (NULL, 'episode', new.id);
+    UPDATE episodes SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
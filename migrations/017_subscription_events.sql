@@ -0,0 +1,66 @@
+-- Migration 017: Subscription history.
+-- Every add/remove is recorded as an event. `subscriptions` becomes the
+-- current state derived from these events by the triggers below: a re-add
+-- after a removal reopens the row instead of being ignored.
+
+CREATE TABLE IF NOT EXISTS subscription_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device_id INTEGER NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+    podcast_url TEXT NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('add', 'remove')),
+    -- When the change happened
+    timestamp INTEGER NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_subscription_events_device
+ON subscription_events(user_id, device_id, podcast_url, id);
+
+-- Backfill the history known so far: each row was added once and possibly
+-- removed afterwards
+INSERT INTO subscription_events (user_id, device_id, podcast_url, action, timestamp)
+SELECT user_id, device_id, podcast_url, action, timestamp
+FROM (
+    SELECT user_id, device_id, podcast_url, 'add' AS action, added_at AS timestamp, id, 0 AS step
+    FROM subscriptions
+    UNION ALL
+    SELECT user_id, device_id, podcast_url, 'remove', removed_at, id, 1
+    FROM subscriptions
+    WHERE removed_at IS NOT NULL
+)
+ORDER BY timestamp, id, step;
+
+CREATE TRIGGER IF NOT EXISTS subscription_events_apply_add
+AFTER INSERT ON subscription_events
+WHEN new.action = 'add'
+BEGIN
+    INSERT INTO subscriptions (user_id, device_id, podcast_url, added_at)
+    VALUES (new.user_id, new.device_id, new.podcast_url, new.timestamp)
+    ON CONFLICT(user_id, device_id, podcast_url) DO UPDATE SET
+        added_at = excluded.added_at,
+        removed_at = NULL
+    WHERE removed_at IS NOT NULL;
+END;
+
+CREATE TRIGGER IF NOT EXISTS subscription_events_apply_remove
+AFTER INSERT ON subscription_events
+WHEN new.action = 'remove'
+BEGIN
+    UPDATE subscriptions
+    SET removed_at = new.timestamp
+    WHERE user_id = new.user_id AND device_id = new.device_id
+      AND podcast_url = new.podcast_url AND removed_at IS NULL;
+END;
+
+-- Reopened subscriptions count as subscribers again
+CREATE TRIGGER IF NOT EXISTS increase_subscriber_count_on_resubscribe
+AFTER UPDATE OF removed_at ON subscriptions
+WHEN NEW.removed_at IS NULL AND OLD.removed_at IS NOT NULL
+BEGIN
+    INSERT INTO podcasts (url, subscriber_count, updated_at)
+    VALUES (NEW.podcast_url, 1, strftime('%s', 'now'))
+    ON CONFLICT(url) DO UPDATE SET
+        subscriber_count = subscriber_count + 1,
+        updated_at = strftime('%s', 'now');
+END;
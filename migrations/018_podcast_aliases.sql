@@ -0,0 +1,15 @@
+-- Migration 018: Podcast URL aliases.
+-- Feeds that moved (HTTP 301/308 or `<itunes:new-feed-url>`) keep being
+-- uploaded under their old URL by clients. Each alias maps such a URL to the
+-- one it is stored under; aliases always point at a canonical URL, never at
+-- another alias.
+
+CREATE TABLE IF NOT EXISTS podcast_aliases (
+    alias_url TEXT PRIMARY KEY,
+    podcast_url TEXT NOT NULL,
+    -- What established the alias
+    source TEXT NOT NULL CHECK (source IN ('redirect', 'new-feed-url', 'admin')),
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_podcast_aliases_podcast ON podcast_aliases(podcast_url);
@@ -0,0 +1,22 @@
+-- Migration 019: Podcast lists.
+-- Curated, publicly readable lists of podcasts as in the gpodder.net
+-- `/api/2/lists` API. Lists are addressed by a URL-safe name derived from
+-- their title, unique per user.
+
+CREATE TABLE IF NOT EXISTS podcast_lists (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    title TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    UNIQUE(user_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS podcast_list_entries (
+    list_id INTEGER NOT NULL REFERENCES podcast_lists(id) ON DELETE CASCADE,
+    podcast_url TEXT NOT NULL,
+    -- Order in which the podcasts were uploaded
+    position INTEGER NOT NULL,
+    PRIMARY KEY (list_id, podcast_url)
+);
@@ -0,0 +1,34 @@
+-- Migration 020: User-defined episode chapters.
+-- Chapter marks a user set on an episode, synchronized between their devices
+-- through `/api/2/chapters`. A chapter is identified by its time range; an
+-- upload of the same range updates its label.
+
+CREATE TABLE IF NOT EXISTS chapters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    podcast_url TEXT NOT NULL,
+    episode_url TEXT NOT NULL,
+    -- Seconds from the start of the episode
+    start INTEGER NOT NULL,
+    "end" INTEGER NOT NULL,
+    label TEXT,
+    advertisement INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    change_seq INTEGER NOT NULL DEFAULT 0,
+    UNIQUE(user_id, episode_url, start, "end")
+);
+
+CREATE INDEX IF NOT EXISTS idx_chapters_change_seq ON chapters(user_id, change_seq);
+
+-- Sync cursor, see migration 016
+CREATE TRIGGER IF NOT EXISTS chapters_change_insert AFTER INSERT ON chapters BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'chapter', new.id);
+    UPDATE chapters SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS chapters_change_update AFTER UPDATE OF label, advertisement ON chapters
+WHEN old.label IS NOT new.label OR old.advertisement IS NOT new.advertisement
+BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'chapter', new.id);
+    UPDATE chapters SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
@@ -0,0 +1,35 @@
+-- Migration 021: Episode bookmarks.
+-- A bookmark is a position in an episode with a free-text note; a user can
+-- set any number per episode. Deleted bookmarks are kept as tombstones
+-- (`deleted_at`) so incremental syncs can tell devices to drop them.
+
+CREATE TABLE IF NOT EXISTS bookmarks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    podcast_url TEXT NOT NULL,
+    episode_url TEXT NOT NULL,
+    -- Seconds from the start of the episode
+    position INTEGER NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    deleted_at INTEGER,
+    change_seq INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_bookmarks_episode ON bookmarks(user_id, episode_url);
+CREATE INDEX IF NOT EXISTS idx_bookmarks_change_seq ON bookmarks(user_id, change_seq);
+
+-- Sync cursor, see migration 016
+CREATE TRIGGER IF NOT EXISTS bookmarks_change_insert AFTER INSERT ON bookmarks BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'bookmark', new.id);
+    UPDATE bookmarks SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS bookmarks_change_update AFTER UPDATE OF position, note, deleted_at ON bookmarks
+WHEN old.position IS NOT new.position OR old.note IS NOT new.note
+    OR old.deleted_at IS NOT new.deleted_at
+BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'bookmark', new.id);
+    UPDATE bookmarks SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
@@ -0,0 +1,11 @@
+-- Migration 022: Device activity.
+-- `last_seen_at` and `user_agent` are updated by every sync request naming
+-- the device; `updated_at` only changes with caption and type. Devices unseen
+-- for longer than the configured retention are archived: hidden from device
+-- lists until they sync again, their data is kept.
+
+ALTER TABLE devices ADD COLUMN last_seen_at INTEGER;
+ALTER TABLE devices ADD COLUMN user_agent TEXT;
+ALTER TABLE devices ADD COLUMN archived_at INTEGER;
+
+UPDATE devices SET last_seen_at = updated_at;
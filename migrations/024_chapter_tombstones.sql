@@ -0,0 +1,16 @@
+-- Migration 024: Keep removed chapters as tombstones.
+-- Like bookmarks, a removed chapter stays with `deleted_at` set so that
+-- incremental syncs can tell devices to drop it; uploading the same range
+-- again revives it.
+
+ALTER TABLE chapters ADD COLUMN deleted_at INTEGER;
+
+DROP TRIGGER IF EXISTS chapters_change_update;
+
+CREATE TRIGGER IF NOT EXISTS chapters_change_update AFTER UPDATE OF label, advertisement, deleted_at ON chapters
+WHEN old.label IS NOT new.label OR old.advertisement IS NOT new.advertisement
+    OR old.deleted_at IS NOT new.deleted_at
+BEGIN
+    INSERT INTO change_log (user_id, entity, entity_id) VALUES (new.user_id, 'chapter', new.id);
+    UPDATE chapters SET change_seq = last_insert_rowid() WHERE id = new.id;
+END;
@@ -8,6 +8,19 @@ pub struct Config {
     pub admin_username: Option<String>,
     pub admin_password: Option<String>,
     pub log_level: String,
+    /// Seconds between feed refreshes, 0 disables the feed fetcher
+    pub feed_refresh_interval: u64,
+    /// Seconds between recomputations of podcast suggestions, 0 disables them
+    pub suggestion_refresh_interval: u64,
+    /// Seconds a client clock may run ahead of the server; later client
+    /// timestamps are capped to this bound
+    pub max_clock_skew: i64,
+    /// Query parameters stripped from podcast and episode URLs; a trailing
+    /// `*` matches any suffix
+    pub tracking_params: Vec<String>,
+    /// Days after which devices that have not synced are archived, 0 keeps
+    /// them forever
+    pub device_retention_days: u64,
 }
 
 impl Config {
@@ -27,6 +40,34 @@ impl Config {
 
         let log_level = env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
 
+        let feed_refresh_interval = env::var("PODSYNQ_FEED_REFRESH_INTERVAL")
+            .ok()
+            .and_then(|i| i.parse().ok())
+            .unwrap_or(3600);
+
+        let suggestion_refresh_interval = env::var("PODSYNQ_SUGGESTION_REFRESH_INTERVAL")
+            .ok()
+            .and_then(|i| i.parse().ok())
+            .unwrap_or(3600);
+
+        let max_clock_skew = env::var("PODSYNQ_MAX_CLOCK_SKEW")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|s: &i64| *s >= 0)
+            .unwrap_or(300);
+
+        let tracking_params = env::var("PODSYNQ_TRACKING_PARAMS")
+            .unwrap_or_else(|_| "utm_*,fbclid,gclid".to_string())
+            .split(',')
+            .map(|param| param.trim().to_string())
+            .filter(|param| !param.is_empty())
+            .collect();
+
+        let device_retention_days = env::var("PODSYNQ_DEVICE_RETENTION_DAYS")
+            .ok()
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(0);
+
         Ok(Self {
             port,
             db_path,
@@ -34,6 +75,11 @@ impl Config {
             admin_username,
             admin_password,
             log_level,
+            feed_refresh_interval,
+            suggestion_refresh_interval,
+            max_clock_skew,
+            tracking_params,
+            device_retention_days,
         })
     }
 
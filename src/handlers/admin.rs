@@ -0,0 +1,457 @@
+use askama::Template;
+use rust_embed::RustEmbed;
+use serde::Deserialize;
+use warp::{
+    http::{header::SET_COOKIE, StatusCode, Uri},
+    reject,
+    reply::{self, html},
+    Rejection, Reply,
+};
+
+use crate::error::AppError;
+use crate::middleware::admin::ADMIN_SESSION_COOKIE;
+use crate::middleware::AdminContext;
+use crate::services::AdminSessionService;
+use crate::state::AppState;
+
+/// Number of episode actions shown on the user detail page
+const RECENT_ACTIONS_LIMIT: i64 = 50;
+
+#[derive(RustEmbed)]
+#[folder = "static/"]
+struct AdminAssets;
+
+#[derive(Debug, Deserialize)]
+pub struct LoginForm {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateUserForm {
+    pub username: String,
+    pub password: String,
+    pub is_admin: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordForm {
+    pub password: String,
+}
+
+struct UserRow {
+    id: i64,
+    username: String,
+    is_admin: bool,
+    devices: usize,
+    subscriptions: i64,
+    created: String,
+}
+
+struct DeviceRow {
+    device_id: String,
+    caption: String,
+    device_type: String,
+    subscriptions: i64,
+    updated: String,
+    /// When the device was archived for inactivity, empty if it is active
+    archived: String,
+}
+
+struct ActionRow {
+    when: String,
+    device: String,
+    action: String,
+    podcast: String,
+    episode: String,
+    position: String,
+}
+
+#[derive(Template)]
+#[template(path = "admin/login.html")]
+struct LoginTemplate {
+    admin_name: Option<String>,
+    error: Option<String>,
+    username: String,
+}
+
+#[derive(Template)]
+#[template(path = "admin/users.html")]
+struct UsersTemplate {
+    admin_name: Option<String>,
+    error: Option<String>,
+    users: Vec<UserRow>,
+}
+
+#[derive(Template)]
+#[template(path = "admin/user.html")]
+struct UserTemplate {
+    admin_name: Option<String>,
+    error: Option<String>,
+    user: UserRow,
+    is_self: bool,
+    devices: Vec<DeviceRow>,
+    subscriptions: Vec<String>,
+    actions: Vec<ActionRow>,
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn render(template: &impl Template) -> Result<String, Rejection> {
+    template
+        .render()
+        .map_err(|e| reject::custom(AppError::Internal(e.to_string())))
+}
+
+fn redirect_to(path: &str) -> Box<dyn Reply + Send> {
+    let uri = Uri::try_from(path).unwrap_or_else(|_| Uri::from_static("/admin"));
+    Box::new(warp::redirect::see_other(uri))
+}
+
+fn login_redirect() -> Box<dyn Reply + Send> {
+    redirect_to("/admin/login")
+}
+
+/// Answer directly instead of rejecting: a `not_found` rejection would be
+/// merged with the `MethodNotAllowed` rejections of the other routes
+fn not_found_page() -> Box<dyn Reply + Send> {
+    Box::new(reply::with_status(
+        html("Not found".to_string()),
+        StatusCode::NOT_FOUND,
+    ))
+}
+
+fn session_cookie(value: &str, max_age: i64) -> String {
+    format!(
+        "{}={}; Max-Age={}; Path=/admin; HttpOnly; SameSite=Strict",
+        ADMIN_SESSION_COOKIE, value, max_age
+    )
+}
+
+/// GET /admin/login
+pub async fn login_page(admin: Option<AdminContext>) -> Result<Box<dyn Reply + Send>, Rejection> {
+    if admin.is_some() {
+        return Ok(redirect_to("/admin"));
+    }
+
+    let page = render(&LoginTemplate {
+        admin_name: None,
+        error: None,
+        username: String::new(),
+    })?;
+    Ok(Box::new(html(page)))
+}
+
+/// POST /admin/login
+pub async fn login(state: AppState, form: LoginForm) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let user = match state
+        .user_service
+        .verify_credentials(&form.username, &form.password)
+        .await
+    {
+        Ok(user) => Some(user),
+        Err(AppError::Authentication) => None,
+        Err(e) => return Err(reject::custom(e)),
+    };
+
+    let Some(user) = user.filter(|user| user.is_admin) else {
+        tracing::warn!("Admin login failed for user: {}", form.username);
+        let page = render(&LoginTemplate {
+            admin_name: None,
+            error: Some("Invalid credentials or missing admin rights".to_string()),
+            username: form.username,
+        })?;
+        return Ok(Box::new(reply::with_status(
+            html(page),
+            StatusCode::UNAUTHORIZED,
+        )));
+    };
+
+    let session_id = state
+        .admin_session_service
+        .create_session(user.id)
+        .await
+        .map_err(reject::custom)?;
+
+    tracing::info!("Admin {} logged in to the web UI", user.username);
+
+    Ok(Box::new(reply::with_header(
+        warp::redirect::see_other(Uri::from_static("/admin")),
+        SET_COOKIE,
+        session_cookie(&session_id, AdminSessionService::session_duration_secs()),
+    )))
+}
+
+/// POST /admin/logout
+pub async fn logout(
+    admin: Option<AdminContext>,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    if let Some(admin) = admin {
+        if let Err(e) = state
+            .admin_session_service
+            .delete_session(&admin.session_id)
+            .await
+        {
+            tracing::warn!("Failed to delete admin session: {:?}", e);
+        }
+    }
+
+    Ok(Box::new(reply::with_header(
+        warp::redirect::see_other(Uri::from_static("/admin/login")),
+        SET_COOKIE,
+        session_cookie("", 0),
+    )))
+}
+
+async fn render_users(
+    admin: &AdminContext,
+    state: &AppState,
+    error: Option<String>,
+) -> Result<String, Rejection> {
+    let mut users = Vec::new();
+    for user in state.user_service.list_users().await? {
+        let devices = state.device_service.list_all_user_devices(user.id).await?;
+        let subscriptions = state
+            .subscription_service
+            .get_all_subscriptions(user.id)
+            .await?;
+
+        users.push(UserRow {
+            id: user.id,
+            username: user.username,
+            is_admin: user.is_admin,
+            devices: devices.len(),
+            subscriptions: subscriptions.len() as i64,
+            created: format_timestamp(user.created_at),
+        });
+    }
+
+    render(&UsersTemplate {
+        admin_name: Some(admin.username.clone()),
+        error,
+        users,
+    })
+}
+
+/// GET /admin
+pub async fn dashboard(
+    admin: Option<AdminContext>,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let Some(admin) = admin else {
+        return Ok(login_redirect());
+    };
+
+    let page = render_users(&admin, &state, None).await?;
+    Ok(Box::new(html(page)))
+}
+
+/// POST /admin/users
+pub async fn create_user(
+    admin: Option<AdminContext>,
+    state: AppState,
+    form: CreateUserForm,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let Some(admin) = admin else {
+        return Ok(login_redirect());
+    };
+
+    let username = form.username.trim();
+    if let Err(e) = state
+        .user_service
+        .register_user(username, &form.password, form.is_admin.is_some())
+        .await
+    {
+        let AppError::BadRequest(message) = e else {
+            return Err(reject::custom(e));
+        };
+        let page = render_users(&admin, &state, Some(message)).await?;
+        return Ok(Box::new(reply::with_status(
+            html(page),
+            StatusCode::BAD_REQUEST,
+        )));
+    }
+
+    tracing::info!("Admin {} created user {}", admin.username, username);
+
+    Ok(redirect_to("/admin"))
+}
+
+async fn render_user_detail(
+    admin: &AdminContext,
+    state: &AppState,
+    user_id: i64,
+    error: Option<String>,
+) -> Result<Option<String>, Rejection> {
+    let Some(user) = state.user_service.find_by_id(user_id).await? else {
+        return Ok(None);
+    };
+
+    let mut devices = Vec::new();
+    for device in state.device_service.list_all_user_devices(user.id).await? {
+        let subscriptions = state
+            .subscription_service
+            .count_subscriptions(user.id, Some(device.id))
+            .await?;
+
+        devices.push(DeviceRow {
+            device_id: device.device_id,
+            caption: device.caption.unwrap_or_default(),
+            device_type: device.r#type.unwrap_or_default(),
+            subscriptions,
+            updated: format_timestamp(device.updated_at),
+            archived: device.archived_at.map(format_timestamp).unwrap_or_default(),
+        });
+    }
+
+    let subscriptions = state
+        .subscription_service
+        .get_all_subscriptions(user.id)
+        .await?;
+
+    let actions = state
+        .episode_action_service
+        .get_recent_actions(user.id, RECENT_ACTIONS_LIMIT)
+        .await?
+        .into_iter()
+        .map(|action| ActionRow {
+            when: format_timestamp(action.timestamp),
+            device: action.device,
+            action: action.action,
+            podcast: action.podcast_url,
+            episode: action.episode_url,
+            position: match (action.position, action.total) {
+                (Some(position), Some(total)) => format!("{} / {} s", position, total),
+                (Some(position), None) => format!("{} s", position),
+                _ => String::new(),
+            },
+        })
+        .collect();
+
+    let page = render(&UserTemplate {
+        admin_name: Some(admin.username.clone()),
+        error,
+        is_self: user.id == admin.user_id,
+        user: UserRow {
+            id: user.id,
+            username: user.username,
+            is_admin: user.is_admin,
+            devices: devices.len(),
+            subscriptions: subscriptions.len() as i64,
+            created: format_timestamp(user.created_at),
+        },
+        devices,
+        subscriptions,
+        actions,
+    })?;
+
+    Ok(Some(page))
+}
+
+/// GET /admin/users/{id}
+pub async fn user_detail(
+    user_id: i64,
+    admin: Option<AdminContext>,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let Some(admin) = admin else {
+        return Ok(login_redirect());
+    };
+
+    match render_user_detail(&admin, &state, user_id, None).await? {
+        Some(page) => Ok(Box::new(html(page))),
+        None => Ok(not_found_page()),
+    }
+}
+
+/// POST /admin/users/{id}/password
+pub async fn reset_password(
+    user_id: i64,
+    admin: Option<AdminContext>,
+    state: AppState,
+    form: PasswordForm,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let Some(admin) = admin else {
+        return Ok(login_redirect());
+    };
+
+    if let Err(e) = state
+        .user_service
+        .reset_password(user_id, &form.password)
+        .await
+    {
+        let message = match e {
+            AppError::BadRequest(message) => message,
+            AppError::NotFound(_) => return Ok(not_found_page()),
+            e => return Err(reject::custom(e)),
+        };
+        return match render_user_detail(&admin, &state, user_id, Some(message)).await? {
+            Some(page) => Ok(Box::new(reply::with_status(
+                html(page),
+                StatusCode::BAD_REQUEST,
+            ))),
+            None => Ok(not_found_page()),
+        };
+    }
+
+    tracing::info!(
+        "Admin {} reset the password of user {}",
+        admin.username,
+        user_id
+    );
+
+    Ok(redirect_to(&format!("/admin/users/{}", user_id)))
+}
+
+/// POST /admin/users/{id}/delete
+pub async fn delete_user(
+    user_id: i64,
+    admin: Option<AdminContext>,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let Some(admin) = admin else {
+        return Ok(login_redirect());
+    };
+
+    if user_id == admin.user_id {
+        return Err(reject::custom(AppError::BadRequest(
+            "Administrators cannot delete their own account".to_string(),
+        )));
+    }
+
+    match state.user_service.delete_user(user_id).await {
+        Ok(()) => {}
+        Err(AppError::NotFound(_)) => return Ok(not_found_page()),
+        Err(e) => return Err(reject::custom(e)),
+    }
+
+    tracing::info!("Admin {} deleted user {}", admin.username, user_id);
+
+    Ok(redirect_to("/admin"))
+}
+
+/// GET /admin/static/{file}
+pub async fn static_asset(path: String) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let Some(asset) = AdminAssets::get(&path) else {
+        return Ok(not_found_page());
+    };
+
+    let content_type = match path.rsplit('.').next() {
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        _ => "application/octet-stream",
+    };
+
+    Ok(Box::new(reply::with_header(
+        asset.data.into_owned(),
+        "content-type",
+        content_type,
+    )))
+}
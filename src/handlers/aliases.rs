@@ -0,0 +1,108 @@
+use serde::Deserialize;
+use warp::{http::StatusCode, reject, reply, reply::json, Rejection, Reply};
+
+use crate::error::AppError;
+use crate::middleware::AuthContext;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAliasRequest {
+    /// URL the podcast was known under
+    pub alias: String,
+    /// URL the podcast moved to
+    pub podcast: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AliasQueryParams {
+    pub url: String,
+}
+
+/// GET /api/admin/aliases
+pub async fn list_aliases(auth: AuthContext, state: AppState) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let aliases = state
+        .url_service
+        .list_aliases()
+        .await
+        .map_err(reject::custom)?;
+
+    Ok(json(&aliases))
+}
+
+/// POST /api/admin/aliases
+///
+/// Moves everything stored under `alias` to `podcast`; later uploads of
+/// `alias` are rewritten as well.
+pub async fn create_alias(
+    auth: AuthContext,
+    state: AppState,
+    req: CreateAliasRequest,
+) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let moved = state
+        .url_service
+        .move_podcast(&req.alias, &req.podcast, "admin")
+        .await
+        .map_err(reject::custom)?;
+    if !moved {
+        return Err(reject::custom(AppError::BadRequest(
+            "Both URLs already denote the same podcast".to_string(),
+        )));
+    }
+
+    tracing::info!(
+        "Admin {} moved podcast {} to {}",
+        auth.username,
+        req.alias,
+        req.podcast
+    );
+
+    Ok(reply::with_status(
+        json(&serde_json::json!({ "status": "ok" })),
+        StatusCode::CREATED,
+    ))
+}
+
+/// DELETE /api/admin/aliases?url={alias}
+pub async fn delete_alias(
+    params: AliasQueryParams,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    state
+        .url_service
+        .delete_alias(&params.url)
+        .await
+        .map_err(reject::custom)?;
+
+    tracing::info!("Admin {} deleted alias {}", auth.username, params.url);
+
+    Ok(json(&serde_json::json!({ "status": "ok" })))
+}
+
+/// POST /api/admin/urls/normalize
+///
+/// Rewrites stored URLs into the current canonical form, e.g. after the
+/// configured tracking parameters changed.
+pub async fn normalize_urls(auth: AuthContext, state: AppState) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let rewritten = state
+        .url_service
+        .normalize_stored_urls()
+        .await
+        .map_err(reject::custom)?;
+
+    tracing::info!(
+        "Admin {} normalized {} stored URLs",
+        auth.username,
+        rewritten
+    );
+
+    Ok(json(&serde_json::json!({ "rewritten": rewritten })))
+}
@@ -5,7 +5,7 @@ use warp::{
     Rejection,
 };
 
-use crate::{middleware::AuthContext, state::AppState};
+use crate::{error::AppError, middleware::AuthContext, state::AppState};
 
 #[derive(Debug, Deserialize)]
 pub struct LogoutRequest {
@@ -13,6 +13,17 @@ pub struct LogoutRequest {
     _session_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: String,
+}
+
 pub async fn login(
     _username: String,
     auth: AuthContext,
@@ -80,6 +91,70 @@ pub async fn logout(
     ))
 }
 
+/// Cookie header that removes the session cookie from the client
+fn clear_session_cookie() -> HeaderValue {
+    HeaderValue::from_static("sessionid=; Max-Age=0; Path=/; HttpOnly; SameSite=Lax")
+}
+
+pub async fn change_password(
+    username: String,
+    auth: AuthContext,
+    state: AppState,
+    req: ChangePasswordRequest,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(warp::reject::custom(AppError::Authorization));
+    }
+
+    state
+        .user_service
+        .change_password(auth.user_id, &req.current_password, &req.new_password)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    // Every existing login, including the current one, has to re-authenticate
+    state
+        .session_service
+        .delete_user_sessions(auth.user_id)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    tracing::info!("User {} changed their password", auth.username);
+
+    Ok(with_header(
+        json(&serde_json::json!({ "status": "ok" })),
+        SET_COOKIE,
+        clear_session_cookie(),
+    ))
+}
+
+pub async fn delete_account(
+    username: String,
+    auth: AuthContext,
+    state: AppState,
+    req: DeleteAccountRequest,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(warp::reject::custom(AppError::Authorization));
+    }
+
+    // Sessions, devices, subscriptions, episode actions, settings and
+    // favorites are removed by the database cascade
+    state
+        .user_service
+        .delete_account(auth.user_id, &req.password)
+        .await
+        .map_err(warp::reject::custom)?;
+
+    tracing::info!("User {} deleted their account", auth.username);
+
+    Ok(with_header(
+        json(&serde_json::json!({ "status": "ok" })),
+        SET_COOKIE,
+        clear_session_cookie(),
+    ))
+}
+
 fn extract_session_from_cookie(cookie_header: &str) -> Option<String> {
     for cookie in cookie_header.split(';') {
         let parts: Vec<&str> = cookie.trim().splitn(2, '=').collect();
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use warp::{http::StatusCode, reject, reply, reply::json, Rejection, Reply};
+
+use crate::error::AppError;
+use crate::handlers::chapters::own_username;
+use crate::middleware::AuthContext;
+use crate::models::{BookmarkMark, BookmarkResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct BookmarkQueryParams {
+    pub since: Option<i64>,
+    pub podcast: Option<String>,
+    pub episode: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BookmarksResult {
+    pub bookmarks: Vec<BookmarkResponse>,
+    /// Bookmarks deleted since the `since` cursor
+    pub deleted: Vec<i64>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BookmarkUpload {
+    pub podcast: String,
+    pub episode: String,
+    pub position: i64,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BookmarkUpdate {
+    pub position: Option<i64>,
+    pub note: Option<String>,
+}
+
+/// Bookmark id of an `{id}.json` path segment
+fn bookmark_id(id_json: &str) -> Result<i64, Rejection> {
+    id_json
+        .strip_suffix(".json")
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(warp::reject::not_found)
+}
+
+/// GET /api/2/bookmarks/{username}.json?since={cursor}&podcast={url}&episode={url}
+pub async fn get_bookmarks(
+    username_json: String,
+    auth: AuthContext,
+    params: BookmarkQueryParams,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    own_username(&username_json, &auth)?;
+
+    let podcast = match &params.podcast {
+        Some(podcast) => Some(state.url_service.sanitize_podcast_url(podcast).await?),
+        None => None,
+    };
+    let episode = params
+        .episode
+        .as_deref()
+        .map(|episode| state.url_service.sanitize_url(episode));
+
+    let cursor = state.change_log_service.current_cursor().await?;
+    let (bookmarks, deleted) = state
+        .bookmark_service
+        .get_bookmarks(
+            auth.user_id,
+            params.since,
+            podcast.as_deref(),
+            episode.as_deref(),
+        )
+        .await?;
+
+    Ok(json(&BookmarksResult {
+        bookmarks: bookmarks
+            .iter()
+            .map(|bookmark| bookmark.to_response())
+            .collect(),
+        deleted,
+        timestamp: cursor,
+    }))
+}
+
+/// POST /api/2/bookmarks/{username}.json
+pub async fn create_bookmark(
+    username_json: String,
+    auth: AuthContext,
+    state: AppState,
+    upload: BookmarkUpload,
+) -> Result<impl Reply, Rejection> {
+    own_username(&username_json, &auth)?;
+
+    let podcast = state
+        .url_service
+        .sanitize_podcast_url(&upload.podcast)
+        .await?;
+    let episode = state.url_service.sanitize_url(&upload.episode);
+    if podcast.is_empty() || episode.is_empty() {
+        return Err(reject::custom(AppError::BadRequest(
+            "Invalid podcast or episode URL".to_string(),
+        )));
+    }
+
+    let bookmark = state
+        .bookmark_service
+        .create_bookmark(
+            auth.user_id,
+            &podcast,
+            &episode,
+            &BookmarkMark {
+                position: upload.position,
+                note: upload.note,
+            },
+        )
+        .await?;
+
+    Ok(reply::with_status(
+        json(&bookmark.to_response()),
+        StatusCode::CREATED,
+    ))
+}
+
+/// PUT /api/2/bookmarks/{username}/{id}.json
+pub async fn update_bookmark(
+    username: String,
+    id_json: String,
+    auth: AuthContext,
+    state: AppState,
+    update: BookmarkUpdate,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+    let id = bookmark_id(&id_json)?;
+
+    let bookmark = state
+        .bookmark_service
+        .update_bookmark(auth.user_id, id, update.position, update.note.as_deref())
+        .await?;
+
+    Ok(json(&bookmark.to_response()))
+}
+
+/// DELETE /api/2/bookmarks/{username}/{id}.json
+pub async fn delete_bookmark(
+    username: String,
+    id_json: String,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+    let id = bookmark_id(&id_json)?;
+
+    state
+        .bookmark_service
+        .delete_bookmark(auth.user_id, id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
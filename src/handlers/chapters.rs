@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use warp::{reject, reply::json, Rejection, Reply};
+
+use crate::error::AppError;
+use crate::middleware::AuthContext;
+use crate::models::{ChapterMark, ChapterRange, ChapterResponse, RemovedChapterResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct ChapterQueryParams {
+    pub since: Option<i64>,
+    pub podcast: Option<String>,
+    pub episode: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChaptersResult {
+    pub chapters: Vec<ChapterResponse>,
+    /// Chapters removed since the `since` cursor
+    pub removed: Vec<RemovedChapterResponse>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChapterUpload {
+    pub podcast: String,
+    pub episode: String,
+    #[serde(default)]
+    pub chapters_add: Vec<ChapterMark>,
+    #[serde(default)]
+    pub chapters_remove: Vec<ChapterRange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChapterUploadResponse {
+    pub timestamp: i64,
+    pub update_urls: Vec<[String; 2]>,
+}
+
+/// Username of a `{username}.json` path segment, which must be the
+/// authenticated user
+pub(crate) fn own_username(username_json: &str, auth: &AuthContext) -> Result<(), Rejection> {
+    let username = username_json
+        .strip_suffix(".json")
+        .ok_or_else(warp::reject::not_found)?;
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+    Ok(())
+}
+
+/// GET /api/2/chapters/{username}.json?since={cursor}&podcast={url}&episode={url}
+pub async fn get_chapters(
+    username_json: String,
+    auth: AuthContext,
+    params: ChapterQueryParams,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    own_username(&username_json, &auth)?;
+
+    let podcast = match &params.podcast {
+        Some(podcast) => Some(state.url_service.sanitize_podcast_url(podcast).await?),
+        None => None,
+    };
+    let episode = params
+        .episode
+        .as_deref()
+        .map(|episode| state.url_service.sanitize_url(episode));
+
+    let cursor = state.change_log_service.current_cursor().await?;
+    let (chapters, removed) = state
+        .chapter_service
+        .get_chapters(
+            auth.user_id,
+            params.since,
+            podcast.as_deref(),
+            episode.as_deref(),
+        )
+        .await?;
+
+    Ok(json(&ChaptersResult {
+        chapters: chapters
+            .iter()
+            .map(|chapter| chapter.to_response())
+            .collect(),
+        removed: removed
+            .iter()
+            .map(|chapter| chapter.to_removed_response())
+            .collect(),
+        timestamp: cursor,
+    }))
+}
+
+/// POST /api/2/chapters/{username}.json
+pub async fn upload_chapters(
+    username_json: String,
+    auth: AuthContext,
+    state: AppState,
+    upload: ChapterUpload,
+) -> Result<impl Reply, Rejection> {
+    own_username(&username_json, &auth)?;
+
+    let podcast = state
+        .url_service
+        .sanitize_podcast_url(&upload.podcast)
+        .await?;
+    let episode = state.url_service.sanitize_url(&upload.episode);
+    if podcast.is_empty() || episode.is_empty() {
+        return Err(reject::custom(AppError::BadRequest(
+            "Invalid podcast or episode URL".to_string(),
+        )));
+    }
+
+    let mut update_urls = Vec::new();
+    if upload.podcast != podcast {
+        update_urls.push([upload.podcast.clone(), podcast.clone()]);
+    }
+    if upload.episode != episode {
+        update_urls.push([upload.episode.clone(), episode.clone()]);
+    }
+
+    let cursor = state.change_log_service.current_cursor().await?;
+    state
+        .chapter_service
+        .upload_chapters(
+            auth.user_id,
+            &podcast,
+            &episode,
+            &upload.chapters_add,
+            &upload.chapters_remove,
+        )
+        .await?;
+
+    Ok(json(&ChapterUploadResponse {
+        timestamp: cursor,
+        update_urls,
+    }))
+}
@@ -3,7 +3,7 @@ use warp::{reject, reply::json, Rejection, Reply};
 
 use crate::error::AppError;
 use crate::middleware::AuthContext;
-use crate::models::PodcastMetadata;
+use crate::models::{EpisodeUpdate, PodcastMetadata};
 use crate::state::AppState;
 
 #[derive(Debug, Serialize)]
@@ -13,6 +13,11 @@ pub struct DeviceInfo {
     #[serde(rename = "type")]
     pub device_type: String,
     pub subscriptions: i64,
+    /// Unix time of the device's last sync request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -22,6 +27,12 @@ pub struct UpdateDeviceRequest {
     pub device_type: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MergeDeviceRequest {
+    /// Device id the device is merged into
+    pub into: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdatesQueryParams {
     pub since: Option<i64>,
@@ -32,7 +43,7 @@ pub struct UpdatesQueryParams {
 pub struct DeviceUpdatesResponse {
     pub add: Vec<PodcastMetadata>,
     pub remove: Vec<String>,
-    pub updates: Vec<serde_json::Value>,
+    pub updates: Vec<EpisodeUpdate>,
     pub timestamp: i64,
 }
 
@@ -66,6 +77,8 @@ pub async fn list_devices(
                 .unwrap_or_else(|| "Unknown Device".to_string()),
             device_type: device.r#type.unwrap_or_else(|| "unknown".to_string()),
             subscriptions: sub_count,
+            last_seen: device.last_seen_at,
+            user_agent: device.user_agent,
         });
     }
 
@@ -85,7 +98,7 @@ pub async fn update_device(
 
     let db_device_id = state
         .device_service
-        .get_or_create_device(
+        .update_device(
             auth.user_id,
             &device_id,
             req.caption.as_deref(),
@@ -93,6 +106,10 @@ pub async fn update_device(
         )
         .await
         .map_err(warp::reject::custom)?;
+    state
+        .device_service
+        .record_seen(db_device_id, auth.user_agent.as_deref())
+        .await?;
 
     tracing::info!(
         "Device {} (ID: {}) updated for user {}",
@@ -106,6 +123,47 @@ pub async fn update_device(
     })))
 }
 
+/// DELETE /api/2/devices/{username}/{device}.json
+pub async fn delete_device(
+    username: String,
+    device_id: String,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+
+    state
+        .device_service
+        .delete_device(auth.user_id, &device_id)
+        .await?;
+
+    Ok(warp::http::StatusCode::NO_CONTENT)
+}
+
+/// POST /api/2/devices/{username}/{device}/merge.json
+pub async fn merge_device(
+    username: String,
+    device_id: String,
+    auth: AuthContext,
+    state: AppState,
+    req: MergeDeviceRequest,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+
+    state
+        .device_service
+        .merge_devices(auth.user_id, &device_id, &req.into)
+        .await?;
+
+    Ok(json(&serde_json::json!({
+        "status": "ok",
+    })))
+}
+
 pub async fn get_device_updates(
     username: String,
     device_id: String,
@@ -122,8 +180,13 @@ pub async fn get_device_updates(
         .find_by_device_id(auth.user_id, &device_id)
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
+    state
+        .device_service
+        .record_seen(db_device_id.id, auth.user_agent.as_deref())
+        .await?;
 
     let since = params.since.unwrap_or(0);
+    let cursor = state.change_log_service.current_cursor().await?;
 
     // Get subscription changes (URLs)
     let (add_urls, remove) = state
@@ -139,36 +202,28 @@ pub async fn get_device_updates(
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
 
-    let updates = if params.include_actions.unwrap_or(false) {
-        let actions = state
-            .episode_action_service
-            .get_actions_since(auth.user_id, Some(db_device_id.id), None, since)
-            .await
-            .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
-
-        actions
-            .into_iter()
-            .map(|action| {
-                serde_json::json!({
-                    "podcast": action.podcast_url,
-                    "url": action.episode_url,
-                    "device": action.device,
-                    "action": action.action,
-                    "timestamp": action.timestamp,
-                    "started": action.started,
-                    "position": action.position,
-                    "total": action.total,
-                })
-            })
-            .collect()
-    } else {
-        Vec::new()
-    };
+    // New episodes of the podcasts the device is currently subscribed to
+    let podcast_urls = state
+        .subscription_service
+        .get_subscriptions(auth.user_id, db_device_id.id)
+        .await
+        .map_err(reject::custom)?;
+
+    let updates = state
+        .episode_service
+        .get_episode_updates(
+            auth.user_id,
+            &podcast_urls,
+            since,
+            params.include_actions.unwrap_or(false),
+        )
+        .await
+        .map_err(reject::custom)?;
 
     Ok(json(&DeviceUpdatesResponse {
         add,
         remove,
         updates,
-        timestamp: chrono::Utc::now().timestamp(),
+        timestamp: cursor,
     }))
 }
@@ -0,0 +1,171 @@
+use serde::Deserialize;
+use warp::{reject, reply::json, Rejection, Reply};
+
+use crate::error::AppError;
+use crate::handlers::format::{
+    invalid_format, json_reply, podcast_list_reply, txt_reply, Format, FormatQueryParams,
+};
+use crate::state::AppState;
+
+/// Upper bound for the number of entries of toplists and tag listings
+const MAX_DIRECTORY_ENTRIES: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQueryParams {
+    pub q: Option<String>,
+    /// `podcasts` (default) or `episodes`
+    pub scope: Option<String>,
+    /// Restricts an episode search to one podcast
+    pub podcast: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PodcastDataQueryParams {
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EpisodeDataQueryParams {
+    pub podcast: Option<String>,
+    pub url: String,
+}
+
+/// Parses the `{count}.json` path segment of the directory endpoints
+pub(crate) fn parse_count(count_with_ext: &str) -> Result<i64, Rejection> {
+    count_with_ext
+        .trim_end_matches(".json")
+        .parse::<i64>()
+        .ok()
+        .filter(|count| *count > 0)
+        .map(|count| count.min(MAX_DIRECTORY_ENTRIES))
+        .ok_or_else(|| {
+            reject::custom(AppError::BadRequest(format!(
+                "Invalid count '{}'",
+                count_with_ext
+            )))
+        })
+}
+
+/// Splits a `{name}.{format}` path segment, defaulting to JSON
+pub(crate) fn split_format(segment: &str) -> (&str, &str) {
+    segment.split_once('.').unwrap_or((segment, "json"))
+}
+
+/// Decodes a percent-encoded path segment
+fn decode_segment(segment: &str) -> Result<String, Rejection> {
+    urlencoding::decode(segment)
+        .map(|decoded| decoded.into_owned())
+        .map_err(|_| reject::custom(AppError::BadRequest("Invalid URL encoding".to_string())))
+}
+
+/// `/toplist/{count}.{format}`
+pub async fn toplist(
+    count_with_format: String,
+    params: FormatQueryParams,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let (count, format) = split_format(&count_with_format);
+    let format = Format::parse(format, params)?;
+    let count = parse_count(count)?;
+    let podcasts = state.podcast_service.toplist(count).await?;
+
+    Ok(podcast_list_reply(&format, "Top Podcasts", &podcasts))
+}
+
+/// `/search.{format}` and `/api/2/search.{format}`, answered from the
+/// full-text index in any of the list formats
+pub async fn search(
+    format: String,
+    params: SearchQueryParams,
+    format_params: FormatQueryParams,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let name = format;
+    let format = Format::parse(&name, format_params)?;
+    let query = params.q.unwrap_or_default();
+    let limit = params
+        .limit
+        .unwrap_or(MAX_DIRECTORY_ENTRIES)
+        .clamp(1, MAX_DIRECTORY_ENTRIES);
+
+    match params.scope.as_deref().unwrap_or("podcasts") {
+        "podcasts" => {
+            let podcasts = state.podcast_service.search(&query, limit).await?;
+            Ok(podcast_list_reply(&format, "Search Results", &podcasts))
+        }
+        "episodes" => {
+            let episodes = state
+                .episode_service
+                .search(&query, params.podcast.as_deref(), limit)
+                .await?;
+
+            // Episodes have no OPML or XML representation
+            match format {
+                Format::Json | Format::Jsonp(_) => Ok(json_reply(&format, &episodes)),
+                Format::Txt => Ok(txt_reply(episodes.iter().map(|e| e.url.as_str()))),
+                Format::Opml | Format::Xml => Err(invalid_format(&name)),
+            }
+        }
+        scope => Err(reject::custom(AppError::BadRequest(format!(
+            "Invalid search scope: {}",
+            scope
+        )))),
+    }
+}
+
+pub async fn podcast_data(
+    params: PodcastDataQueryParams,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    let podcast = state.podcast_service.get_podcast(&params.url).await?;
+
+    Ok(json(&podcast))
+}
+
+pub async fn episode_data(
+    params: EpisodeDataQueryParams,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    let episode = state
+        .episode_service
+        .get_episode(params.podcast.as_deref(), &params.url)
+        .await?;
+
+    Ok(json(&episode))
+}
+
+pub async fn top_tags(count_with_ext: String, state: AppState) -> Result<impl Reply, Rejection> {
+    let count = parse_count(&count_with_ext)?;
+    let tags = state.podcast_service.top_tags(count).await?;
+
+    Ok(json(&tags))
+}
+
+pub async fn podcasts_for_tag(
+    tag: String,
+    count_with_ext: String,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    let tag = decode_segment(&tag)?;
+    let count = parse_count(&count_with_ext)?;
+    let podcasts = state.podcast_service.podcasts_for_tag(&tag, count).await?;
+
+    Ok(json(&podcasts))
+}
+
+/// Target of `PodcastMetadata::mygpo_link`
+pub async fn podcast_page(encoded_url: String, state: AppState) -> Result<impl Reply, Rejection> {
+    let url = decode_segment(&encoded_url)?;
+    let podcast = state.podcast_service.get_podcast(&url).await?;
+
+    Ok(json(&podcast))
+}
+
+/// Target of `EpisodeData::mygpo_link`
+pub async fn episode_page(encoded_url: String, state: AppState) -> Result<impl Reply, Rejection> {
+    let url = decode_segment(&encoded_url)?;
+    let episode = state.episode_service.get_episode(None, &url).await?;
+
+    Ok(json(&episode))
+}
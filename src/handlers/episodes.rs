@@ -1,10 +1,10 @@
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
 use warp::{reject, reply::json, Rejection, Reply};
 
 use crate::error::AppError;
 use crate::middleware::AuthContext;
 use crate::models::{EpisodeAction, EpisodeActionQuery};
+use crate::services::validate_action;
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -19,6 +19,8 @@ pub struct EpisodeActionQueryParams {
 pub struct EpisodeActionResponse {
     pub podcast: String,
     pub episode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>,
     pub action: String,
     pub timestamp: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -28,6 +30,11 @@ pub struct EpisodeActionResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total: Option<i64>,
     pub device: String,
+    /// Episode title from the catalogue, if the episode is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub podcast_title: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -46,6 +53,24 @@ pub async fn get_episode_actions(
         return Err(reject::custom(AppError::Authorization));
     }
 
+    // Downloads without `device` cannot be attributed to a device
+    if let Some(device_id) = &params.device {
+        match state
+            .device_service
+            .find_by_device_id(auth.user_id, device_id)
+            .await
+        {
+            Ok(device) => {
+                state
+                    .device_service
+                    .record_seen(device.id, auth.user_agent.as_deref())
+                    .await?
+            }
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(reject::custom(e)),
+        }
+    }
+
     let query = EpisodeActionQuery {
         since: params.since,
         podcast: params.podcast.clone(),
@@ -53,34 +78,45 @@ pub async fn get_episode_actions(
         aggregated: params.aggregated,
     };
 
+    let cursor = state.change_log_service.current_cursor().await?;
     let actions = state
         .episode_action_service
         .get_episode_actions(auth.user_id, query)
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
 
-    let current_timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
+    let mut episode_urls: Vec<String> = actions.iter().map(|a| a.episode_url.clone()).collect();
+    episode_urls.sort();
+    episode_urls.dedup();
+    let episodes = state
+        .episode_service
+        .get_by_urls(&episode_urls)
+        .await
+        .map_err(reject::custom)?;
 
     let response_actions: Vec<EpisodeActionResponse> = actions
         .into_iter()
-        .map(|action| EpisodeActionResponse {
-            podcast: action.podcast_url,
-            episode: action.episode_url,
-            action: action.action,
-            timestamp: action.timestamp,
-            started: action.started,
-            position: action.position,
-            total: action.total,
-            device: action.device,
+        .map(|action| {
+            let episode = episodes.get(&action.episode_url);
+            EpisodeActionResponse {
+                title: episode.and_then(|e| e.title.clone()),
+                podcast_title: episode.and_then(|e| e.podcast_title.clone()),
+                podcast: action.podcast_url,
+                episode: action.episode_url,
+                guid: action.guid,
+                action: action.action,
+                timestamp: action.timestamp,
+                started: action.started,
+                position: action.position,
+                total: action.total,
+                device: action.device,
+            }
         })
         .collect();
 
     let result = EpisodeActionsResult {
         actions: response_actions,
-        timestamp: current_timestamp,
+        timestamp: cursor,
     };
 
     Ok(json(&result))
@@ -90,19 +126,40 @@ pub async fn get_episode_actions(
 pub struct EpisodeActionUpload {
     pub podcast: String,
     pub episode: String,
+    pub guid: Option<String>,
     pub device: String,
     pub action: String,
+    /// Unix timestamp or ISO 8601 string as sent by gPodder desktop
+    #[serde(deserialize_with = "crate::utils::deserialize_timestamp")]
     pub timestamp: i64,
     pub started: Option<i64>,
     pub position: Option<i64>,
     pub total: Option<i64>,
 }
 
+/// An action of an upload batch that was not stored
+#[derive(Debug, Serialize)]
+pub struct EpisodeActionError {
+    /// Position of the action in the uploaded list
+    pub index: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EpisodeActionUploadResponse {
+    pub timestamp: i64,
+    pub update_urls: Vec<[String; 2]>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<EpisodeActionError>,
+}
+
+/// Stores the valid actions of a batch; invalid ones are reported per item
+/// instead of rejecting the whole upload
 pub async fn upload_episode_actions(
     username: String,
     auth: AuthContext,
     state: AppState,
-    actions: Vec<EpisodeActionUpload>,
+    actions: Vec<serde_json::Value>,
 ) -> Result<impl Reply, Rejection> {
     if username != auth.username {
         return Err(warp::reject::custom(crate::error::AppError::Authorization));
@@ -110,12 +167,27 @@ pub async fn upload_episode_actions(
 
     let mut resolved_actions = Vec::new();
     let mut all_update_urls = Vec::new();
+    let mut errors = Vec::new();
 
     // Resolve device strings to device IDs, creating devices if needed
-    for action in actions {
+    for (index, value) in actions.into_iter().enumerate() {
+        let action = match serde_json::from_value::<EpisodeActionUpload>(value) {
+            Ok(action) => action,
+            Err(e) => {
+                errors.push(EpisodeActionError {
+                    index,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+
         // Sanitize URLs
-        let sanitized_podcast = crate::utils::sanitize_url(&action.podcast);
-        let sanitized_episode = crate::utils::sanitize_url(&action.episode);
+        let sanitized_podcast = state
+            .url_service
+            .sanitize_podcast_url(&action.podcast)
+            .await?;
+        let sanitized_episode = state.url_service.sanitize_url(&action.episode);
 
         // Track URL changes
         if action.podcast != sanitized_podcast {
@@ -125,35 +197,56 @@ pub async fn upload_episode_actions(
             all_update_urls.push([action.episode.clone(), sanitized_episode.clone()]);
         }
 
-        // Get or create device
-        let device_db_id = state
-            .device_service
-            .get_or_create_device(auth.user_id, &action.device, None, None)
-            .await
-            .map_err(|e| warp::reject::custom(AppError::Internal(e.to_string())))?;
-
-        resolved_actions.push(EpisodeAction {
+        let mut resolved = EpisodeAction {
             id: 0, // Will be auto-generated by database
             user_id: auth.user_id,
-            device_id: device_db_id,
+            device_id: 0,
             podcast_url: sanitized_podcast,
             episode_url: sanitized_episode,
-            action: action.action,
+            guid: action.guid,
+            action: action.action.to_lowercase(),
             timestamp: action.timestamp,
             started: action.started,
             position: action.position,
             total: action.total,
             created_at: 0, // Will be set by database
-        });
+        };
+
+        if let Err(message) = validate_action(&resolved) {
+            errors.push(EpisodeActionError { index, message });
+            continue;
+        }
+
+        // Get or create device
+        resolved.device_id = state
+            .device_service
+            .get_or_create_device(auth.user_id, &action.device, None, None)
+            .await
+            .map_err(|e| warp::reject::custom(AppError::Internal(e.to_string())))?;
+
+        resolved_actions.push(resolved);
+    }
+
+    let mut seen_devices: Vec<i64> = resolved_actions.iter().map(|a| a.device_id).collect();
+    seen_devices.sort_unstable();
+    seen_devices.dedup();
+    for device_id in seen_devices {
+        state
+            .device_service
+            .record_seen(device_id, auth.user_agent.as_deref())
+            .await?;
     }
 
+    let cursor = state.change_log_service.current_cursor().await?;
     state
         .episode_action_service
         .upload_episode_actions(resolved_actions)
         .await
         .map_err(warp::reject::custom)?;
 
-    Ok(json(&serde_json::json!({
-        "update_urls": all_update_urls,
-    })))
+    Ok(json(&EpisodeActionUploadResponse {
+        timestamp: cursor,
+        update_urls: all_update_urls,
+        errors,
+    }))
 }
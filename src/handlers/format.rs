@@ -0,0 +1,191 @@
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde::{Deserialize, Serialize};
+use warp::{
+    reject,
+    reply::{self, json},
+    Rejection, Reply,
+};
+
+use crate::error::AppError;
+use crate::models::PodcastMetadata;
+use crate::utils::{parse_opml, write_opml, OpmlOutline};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FormatQueryParams {
+    /// Callback of the `jsonp` format
+    pub jsonp: Option<String>,
+}
+
+/// Output formats of the simple API and the directory's podcast lists
+#[derive(Debug, Clone, PartialEq)]
+pub enum Format {
+    Json,
+    /// JSON wrapped in a call of the given function
+    Jsonp(String),
+    Opml,
+    Txt,
+    Xml,
+}
+
+/// JavaScript identifiers, optionally dotted (`app.callback`); anything else
+/// could inject script into the page loading the response
+fn is_valid_callback(callback: &str) -> bool {
+    callback.split('.').all(|part| {
+        let mut chars = part.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    })
+}
+
+impl Format {
+    /// Format of a `{name}.{format}` path segment
+    pub fn parse(format: &str, params: FormatQueryParams) -> Result<Self, Rejection> {
+        match format {
+            "json" => Ok(Self::Json),
+            "opml" => Ok(Self::Opml),
+            "txt" => Ok(Self::Txt),
+            "xml" => Ok(Self::Xml),
+            "jsonp" => match params.jsonp {
+                Some(callback) if is_valid_callback(&callback) => Ok(Self::Jsonp(callback)),
+                Some(_) => Err(reject::custom(AppError::BadRequest(
+                    "Invalid jsonp callback".to_string(),
+                ))),
+                None => Err(reject::custom(AppError::BadRequest(
+                    "The jsonp format requires a jsonp parameter".to_string(),
+                ))),
+            },
+            _ => Err(invalid_format(format)),
+        }
+    }
+}
+
+pub fn invalid_format(format: &str) -> Rejection {
+    reject::custom(AppError::BadRequest(format!("Invalid format: {}", format)))
+}
+
+/// Podcast URLs of an uploaded list in `json`, `txt` or `opml` format
+pub fn parse_podcast_list(format: &str, body: &[u8]) -> Result<Vec<String>, Rejection> {
+    let bad_request = |message: String| reject::custom(AppError::BadRequest(message));
+
+    match format {
+        "json" => {
+            serde_json::from_slice(body).map_err(|e| bad_request(format!("Invalid JSON: {}", e)))
+        }
+        "txt" => Ok(std::str::from_utf8(body)
+            .map_err(|e| bad_request(format!("Invalid text: {}", e)))?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()),
+        "opml" => Ok(parse_opml(body)
+            .map_err(|e| bad_request(format!("Invalid OPML: {}", e)))?
+            .into_iter()
+            .map(|outline| outline.url)
+            .collect()),
+        _ => Err(invalid_format(format)),
+    }
+}
+
+/// `value` as JSON, wrapped in the callback for `jsonp`
+pub fn json_reply<T: Serialize>(format: &Format, value: &T) -> Box<dyn Reply + Send> {
+    match format {
+        Format::Jsonp(callback) => Box::new(reply::with_header(
+            format!(
+                "{}({})",
+                callback,
+                serde_json::to_string(value).unwrap_or_default()
+            ),
+            "content-type",
+            "application/javascript",
+        )),
+        _ => Box::new(json(value)),
+    }
+}
+
+/// One entry per line
+pub fn txt_reply<'a>(lines: impl IntoIterator<Item = &'a str>) -> Box<dyn Reply + Send> {
+    Box::new(reply::with_header(
+        lines.into_iter().collect::<Vec<_>>().join("\n"),
+        "content-type",
+        "text/plain",
+    ))
+}
+
+/// OPML document listing `podcasts` with their titles and websites
+pub fn podcasts_to_opml(title: &str, podcasts: &[PodcastMetadata]) -> String {
+    let outlines: Vec<OpmlOutline> = podcasts
+        .iter()
+        .map(|podcast| OpmlOutline {
+            url: podcast.url.clone(),
+            title: Some(podcast.title.clone()),
+            website: podcast.website.clone(),
+        })
+        .collect();
+    write_opml(title, &outlines)
+}
+
+fn write_podcasts(
+    writer: &mut Writer<Vec<u8>>,
+    podcasts: &[PodcastMetadata],
+) -> std::io::Result<()> {
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Start(BytesStart::new("podcasts")))?;
+
+    for podcast in podcasts {
+        let subscribers = podcast.subscribers.to_string();
+        let fields = [
+            ("title", Some(podcast.title.as_str())),
+            ("url", Some(podcast.url.as_str())),
+            ("website", podcast.website.as_deref()),
+            ("mygpo_link", Some(podcast.mygpo_link.as_str())),
+            ("description", Some(podcast.description.as_str())),
+            ("subscribers", Some(subscribers.as_str())),
+            ("logo_url", podcast.logo_url.as_deref()),
+        ];
+
+        writer.write_event(Event::Start(BytesStart::new("podcast")))?;
+        for (name, value) in fields {
+            writer.write_event(Event::Start(BytesStart::new(name)))?;
+            writer.write_event(Event::Text(BytesText::new(value.unwrap_or_default())))?;
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("podcast")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("podcasts")))?;
+    Ok(())
+}
+
+/// gpodder.net's XML podcast list
+pub fn podcasts_to_xml(podcasts: &[PodcastMetadata]) -> String {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    write_podcasts(&mut writer, podcasts).expect("writing to memory cannot fail");
+    String::from_utf8(writer.into_inner()).expect("XML is written as UTF-8")
+}
+
+/// Renders a list of podcasts in any of the formats; JSON lists the full
+/// metadata, TXT only the feed URLs
+pub fn podcast_list_reply(
+    format: &Format,
+    title: &str,
+    podcasts: &[PodcastMetadata],
+) -> Box<dyn Reply + Send> {
+    match format {
+        Format::Json | Format::Jsonp(_) => json_reply(format, &podcasts),
+        Format::Opml => Box::new(reply::with_header(
+            podcasts_to_opml(title, podcasts),
+            "content-type",
+            "text/xml",
+        )),
+        Format::Xml => Box::new(reply::with_header(
+            podcasts_to_xml(podcasts),
+            "content-type",
+            "text/xml",
+        )),
+        Format::Txt => txt_reply(podcasts.iter().map(|podcast| podcast.url.as_str())),
+    }
+}
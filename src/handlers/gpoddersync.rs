@@ -0,0 +1,240 @@
+//! Nextcloud "gPodder Sync" app protocol (`/index.php/apps/gpoddersync/...`),
+//! used by AntennaPod and other clients configured for Nextcloud
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use warp::{reject, reply::json, Rejection, Reply};
+
+use crate::error::AppError;
+use crate::handlers::episodes::EpisodeActionError;
+use crate::middleware::AuthContext;
+use crate::models::{EpisodeAction, EpisodeActionQuery, SubscriptionChanges};
+use crate::services::validate_action;
+use crate::state::AppState;
+
+/// Device all gpoddersync clients of a user sync through. Joining it into a
+/// sync group shares its subscriptions with gpodder v2 devices.
+const GPODDERSYNC_DEVICE: &str = "gpoddersync";
+
+/// Timestamp format of the protocol, always UTC
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Debug, Deserialize)]
+pub struct SinceQueryParams {
+    pub since: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionChangesResponse {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscriptionChangeRequest {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GpodderSyncEpisodeAction {
+    pub podcast: String,
+    pub episode: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guid: Option<String>,
+    /// Upper case action name, e.g. `PLAY`
+    pub action: String,
+    pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EpisodeActionsResponse {
+    pub actions: Vec<GpodderSyncEpisodeAction>,
+    pub timestamp: i64,
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format(TIMESTAMP_FORMAT)
+        .to_string()
+}
+
+async fn gpoddersync_device(auth: &AuthContext, state: &AppState) -> Result<i64, Rejection> {
+    let device_id = state
+        .device_service
+        .get_or_create_device(
+            auth.user_id,
+            GPODDERSYNC_DEVICE,
+            Some("gPodder Sync"),
+            Some("mobile"),
+        )
+        .await
+        .map_err(reject::custom)?;
+    state
+        .device_service
+        .record_seen(device_id, auth.user_agent.as_deref())
+        .await?;
+    Ok(device_id)
+}
+
+pub async fn get_subscriptions(
+    params: SinceQueryParams,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    let device_id = gpoddersync_device(&auth, &state).await?;
+
+    let cursor = state.change_log_service.current_cursor().await?;
+    let (add, remove) = state
+        .subscription_service
+        .get_changes_since(auth.user_id, device_id, params.since.unwrap_or(0))
+        .await
+        .map_err(reject::custom)?;
+
+    Ok(json(&SubscriptionChangesResponse {
+        add,
+        remove,
+        timestamp: cursor,
+    }))
+}
+
+pub async fn upload_subscription_changes(
+    auth: AuthContext,
+    state: AppState,
+    req: SubscriptionChangeRequest,
+) -> Result<impl Reply, Rejection> {
+    let device_id = gpoddersync_device(&auth, &state).await?;
+
+    let (add, _) = state.url_service.sanitize_urls(&req.add).await?;
+    let (remove, _) = state.url_service.sanitize_urls(&req.remove).await?;
+    let add: Vec<String> = add.into_iter().filter(|url| !url.is_empty()).collect();
+    let remove: Vec<String> = remove.into_iter().filter(|url| !url.is_empty()).collect();
+
+    if let Some(url) = add.iter().find(|url| remove.contains(url)) {
+        return Err(reject::custom(AppError::BadRequest(format!(
+            "URL cannot be both added and removed: {}",
+            url
+        ))));
+    }
+
+    let cursor = state.change_log_service.current_cursor().await?;
+    state
+        .subscription_service
+        .upload_changes(
+            auth.user_id,
+            device_id,
+            SubscriptionChanges {
+                add,
+                remove,
+                timestamp: chrono::Utc::now().timestamp(),
+            },
+        )
+        .await
+        .map_err(reject::custom)?;
+
+    Ok(json(&serde_json::json!({ "timestamp": cursor })))
+}
+
+pub async fn get_episode_actions(
+    params: SinceQueryParams,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    let query = EpisodeActionQuery {
+        since: params.since,
+        podcast: None,
+        device: None,
+        aggregated: None,
+    };
+
+    let cursor = state.change_log_service.current_cursor().await?;
+    let actions = state
+        .episode_action_service
+        .get_episode_actions(auth.user_id, query)
+        .await
+        .map_err(reject::custom)?;
+
+    let actions = actions
+        .into_iter()
+        .map(|action| GpodderSyncEpisodeAction {
+            podcast: action.podcast_url,
+            episode: action.episode_url,
+            guid: action.guid,
+            action: action.action.to_uppercase(),
+            timestamp: format_timestamp(action.timestamp),
+            started: action.started,
+            position: action.position,
+            total: action.total,
+        })
+        .collect();
+
+    Ok(json(&EpisodeActionsResponse {
+        actions,
+        timestamp: cursor,
+    }))
+}
+
+pub async fn upload_episode_actions(
+    auth: AuthContext,
+    state: AppState,
+    actions: Vec<GpodderSyncEpisodeAction>,
+) -> Result<impl Reply, Rejection> {
+    let device_id = gpoddersync_device(&auth, &state).await?;
+
+    let mut resolved_actions = Vec::with_capacity(actions.len());
+    let mut errors = Vec::new();
+    for (index, action) in actions.into_iter().enumerate() {
+        let Some(timestamp) = crate::utils::parse_timestamp(&action.timestamp) else {
+            errors.push(EpisodeActionError {
+                index,
+                message: format!("Invalid timestamp '{}'", action.timestamp),
+            });
+            continue;
+        };
+
+        let resolved = EpisodeAction {
+            id: 0,
+            user_id: auth.user_id,
+            device_id,
+            podcast_url: state
+                .url_service
+                .sanitize_podcast_url(&action.podcast)
+                .await?,
+            episode_url: state.url_service.sanitize_url(&action.episode),
+            guid: action.guid,
+            action: action.action.to_lowercase(),
+            timestamp,
+            started: action.started,
+            position: action.position,
+            total: action.total,
+            created_at: 0,
+        };
+
+        match validate_action(&resolved) {
+            Ok(()) => resolved_actions.push(resolved),
+            Err(message) => errors.push(EpisodeActionError { index, message }),
+        }
+    }
+
+    let cursor = state.change_log_service.current_cursor().await?;
+    state
+        .episode_action_service
+        .upload_episode_actions(resolved_actions)
+        .await
+        .map_err(reject::custom)?;
+
+    Ok(json(&serde_json::json!({
+        "timestamp": cursor,
+        "errors": errors,
+    })))
+}
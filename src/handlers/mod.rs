@@ -1,8 +1,18 @@
+pub mod admin;
+pub mod aliases;
 pub mod auth;
+pub mod bookmarks;
+pub mod chapters;
 pub mod clientconfig;
 pub mod device_sync;
 pub mod devices;
+pub mod directory;
 pub mod episodes;
 pub mod favorites;
+pub mod format;
+pub mod gpoddersync;
+pub mod podcast_lists;
 pub mod settings;
 pub mod subscriptions;
+pub mod suggestions;
+pub mod users;
@@ -0,0 +1,161 @@
+use bytes::Bytes;
+use serde::Deserialize;
+use warp::{
+    http::{header, StatusCode},
+    reject,
+    reply::{self, json},
+    Rejection, Reply,
+};
+
+use crate::error::AppError;
+use crate::handlers::directory::split_format;
+use crate::handlers::format::{parse_podcast_list, podcast_list_reply, Format, FormatQueryParams};
+use crate::middleware::AuthContext;
+use crate::models::User;
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateListQueryParams {
+    pub title: Option<String>,
+}
+
+/// Lists are public; look up their owner by name
+async fn find_owner(state: &AppState, username: &str) -> Result<User, Rejection> {
+    state
+        .user_service
+        .find_by_username(username)
+        .await?
+        .ok_or_else(|| reject::custom(AppError::NotFound(format!("User '{}' not found", username))))
+}
+
+/// Normalized podcast URLs of an uploaded list, dropping invalid ones
+async fn uploaded_podcasts(
+    state: &AppState,
+    format: &str,
+    body: &Bytes,
+) -> Result<Vec<String>, Rejection> {
+    let podcast_urls = parse_podcast_list(format, body)?;
+    let (podcast_urls, _) = state.url_service.sanitize_urls(&podcast_urls).await?;
+    Ok(podcast_urls
+        .into_iter()
+        .filter(|url| !url.is_empty())
+        .collect())
+}
+
+/// POST /api/2/lists/{username}/create.{format}?title={title}
+///
+/// Redirects to the new list.
+pub async fn create_list(
+    username: String,
+    format: String,
+    params: CreateListQueryParams,
+    auth: AuthContext,
+    state: AppState,
+    body: Bytes,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+
+    let title = params
+        .title
+        .ok_or_else(|| reject::custom(AppError::BadRequest("Missing list title".to_string())))?;
+    let podcast_urls = uploaded_podcasts(&state, &format, &body).await?;
+
+    let list = state
+        .podcast_list_service
+        .create_list(auth.user_id, &title, podcast_urls)
+        .await?;
+
+    let location = format!(
+        "/api/2/lists/{}/list/{}.{}",
+        urlencoding::encode(&username),
+        urlencoding::encode(&list.name),
+        format
+    );
+    Ok(reply::with_header(
+        reply::with_status(reply::reply(), StatusCode::SEE_OTHER),
+        header::LOCATION,
+        location,
+    ))
+}
+
+/// GET /api/2/lists/{username}.json
+pub async fn get_lists(username_json: String, state: AppState) -> Result<impl Reply, Rejection> {
+    let username = username_json
+        .strip_suffix(".json")
+        .ok_or_else(warp::reject::not_found)?;
+    let owner = find_owner(&state, username).await?;
+
+    let lists = state
+        .podcast_list_service
+        .list_lists(owner.id, &owner.username)
+        .await?;
+
+    Ok(json(&lists))
+}
+
+/// GET /api/2/lists/{username}/list/{name}.{format}
+pub async fn get_list(
+    username: String,
+    name_with_format: String,
+    params: FormatQueryParams,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let (name, format) = split_format(&name_with_format);
+    let format = Format::parse(format, params)?;
+    let owner = find_owner(&state, &username).await?;
+
+    let (list, podcast_urls) = state.podcast_list_service.get_list(owner.id, name).await?;
+    let podcasts = state
+        .podcast_service
+        .get_metadata_for_urls(&podcast_urls)
+        .await?;
+
+    Ok(podcast_list_reply(&format, &list.title, &podcasts))
+}
+
+/// PUT /api/2/lists/{username}/list/{name}.{format}
+///
+/// Replaces the podcasts of the list.
+pub async fn update_list(
+    username: String,
+    name_with_format: String,
+    auth: AuthContext,
+    state: AppState,
+    body: Bytes,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+
+    let (name, format) = split_format(&name_with_format);
+    let podcast_urls = uploaded_podcasts(&state, format, &body).await?;
+
+    state
+        .podcast_list_service
+        .update_list(auth.user_id, name, podcast_urls)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// DELETE /api/2/lists/{username}/list/{name}.{format}
+pub async fn delete_list(
+    username: String,
+    name_with_format: String,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+
+    let (name, _) = split_format(&name_with_format);
+    state
+        .podcast_list_service
+        .delete_list(auth.user_id, name)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -3,9 +3,13 @@ use warp::{reject, reply::json, Rejection, Reply};
 
 use crate::error::AppError;
 use crate::middleware::AuthContext;
-use crate::models::{FavoriteMetadata, SettingRequest};
+use crate::models::{BookmarkMark, FavoriteMetadata, SettingRequest};
 use crate::state::AppState;
 
+/// Episode setting backed by the bookmarks API, for clients that only speak
+/// gpodder settings
+const BOOKMARKS_KEY: &str = "bookmarks";
+
 #[derive(Debug, Deserialize)]
 pub struct SettingsQueryParams {
     pub podcast: Option<String>,
@@ -13,13 +17,91 @@ pub struct SettingsQueryParams {
     pub episode: Option<String>,
 }
 
+/// Scope of a `{scope}.json` path segment
+fn settings_scope(scope_json: &str) -> Result<&str, Rejection> {
+    scope_json
+        .strip_suffix(".json")
+        .ok_or_else(warp::reject::not_found)
+}
+
+/// Adds the episode's bookmarks to its settings, if it has any
+async fn add_bookmarks_setting(
+    state: &AppState,
+    user_id: i64,
+    episode_url: &str,
+    settings: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<(), Rejection> {
+    let episode_url = state.url_service.sanitize_url(episode_url);
+    let marks = state
+        .bookmark_service
+        .episode_bookmarks(user_id, &episode_url)
+        .await?;
+    if !marks.is_empty() {
+        settings.insert(BOOKMARKS_KEY.to_string(), serde_json::json!(marks));
+    }
+    Ok(())
+}
+
+/// Applies and takes the `bookmarks` key out of a settings request; setting
+/// it replaces the episode's bookmarks, removing it deletes them. The
+/// podcast is looked up in the episode catalogue if not given.
+async fn save_bookmarks_setting(
+    state: &AppState,
+    user_id: i64,
+    podcast_url: Option<&str>,
+    episode_url: &str,
+    req: &mut SettingRequest,
+) -> Result<(), Rejection> {
+    let set = req.set.as_mut().and_then(|set| set.remove(BOOKMARKS_KEY));
+    let removed = req.remove.as_mut().is_some_and(|remove| {
+        let len = remove.len();
+        remove.retain(|key| key != BOOKMARKS_KEY);
+        remove.len() != len
+    });
+
+    let marks: Vec<BookmarkMark> = match set {
+        Some(value) => serde_json::from_value(value).map_err(|e| {
+            reject::custom(AppError::BadRequest(format!("Invalid bookmarks: {}", e)))
+        })?,
+        None if removed => Vec::new(),
+        None => return Ok(()),
+    };
+
+    let episode_url = state.url_service.sanitize_url(episode_url);
+    let podcast_url = match podcast_url {
+        Some(podcast_url) => state.url_service.sanitize_podcast_url(podcast_url).await?,
+        None => state
+            .episode_service
+            .get_by_urls(std::slice::from_ref(&episode_url))
+            .await?
+            .remove(&episode_url)
+            .map(|episode| episode.podcast_url)
+            .ok_or_else(|| {
+                reject::custom(AppError::BadRequest(
+                    "Bookmarks need the podcast of the episode".to_string(),
+                ))
+            })?,
+    };
+    if podcast_url.is_empty() || episode_url.is_empty() {
+        return Err(reject::custom(AppError::BadRequest(
+            "Invalid podcast or episode URL".to_string(),
+        )));
+    }
+    state
+        .bookmark_service
+        .set_episode_bookmarks(user_id, &podcast_url, &episode_url, &marks)
+        .await?;
+    Ok(())
+}
+
 pub async fn get_settings(
     username: String,
-    scope: String,
+    scope_json: String,
     params: SettingsQueryParams,
     auth: AuthContext,
     state: AppState,
 ) -> Result<impl Reply, Rejection> {
+    let scope = settings_scope(&scope_json)?;
     if username != auth.username {
         return Err(reject::custom(AppError::Authorization));
     }
@@ -35,11 +117,11 @@ pub async fn get_settings(
         None
     };
 
-    let settings = state
+    let mut settings = state
         .setting_service
         .get_settings(
             auth.user_id,
-            &scope,
+            scope,
             params.podcast.as_deref(),
             device_id,
             params.episode.as_deref(),
@@ -47,17 +129,22 @@ pub async fn get_settings(
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
 
+    if let Some(episode_url) = params.episode.as_deref().filter(|_| scope == "episode") {
+        add_bookmarks_setting(&state, auth.user_id, episode_url, &mut settings).await?;
+    }
+
     Ok(json(&settings))
 }
 
 pub async fn save_settings(
     username: String,
-    scope: String,
+    scope_json: String,
     params: SettingsQueryParams,
     auth: AuthContext,
     state: AppState,
-    req: SettingRequest,
+    mut req: SettingRequest,
 ) -> Result<impl Reply, Rejection> {
+    let scope = settings_scope(&scope_json)?;
     if username != auth.username {
         return Err(reject::custom(AppError::Authorization));
     }
@@ -110,6 +197,15 @@ pub async fn save_settings(
                 }
             }
 
+            save_bookmarks_setting(
+                &state,
+                auth.user_id,
+                params.podcast.as_deref(),
+                episode_url,
+                &mut req,
+            )
+            .await?;
+
             // Check if is_favorite is being removed
             if let Some(ref remove_list) = req.remove {
                 if remove_list.contains(&"is_favorite".to_string()) {
@@ -124,11 +220,11 @@ pub async fn save_settings(
         }
     }
 
-    let settings = state
+    let mut settings = state
         .setting_service
         .save_settings(
             auth.user_id,
-            &scope,
+            scope,
             params.podcast.as_deref(),
             device_id,
             params.episode.as_deref(),
@@ -137,5 +233,9 @@ pub async fn save_settings(
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
 
+    if let Some(episode_url) = params.episode.as_deref().filter(|_| scope == "episode") {
+        add_bookmarks_setting(&state, auth.user_id, episode_url, &mut settings).await?;
+    }
+
     Ok(json(&settings))
 }
@@ -7,8 +7,11 @@ use warp::{
 };
 
 use crate::error::AppError;
+use crate::handlers::format::{
+    json_reply, parse_podcast_list, podcast_list_reply, Format, FormatQueryParams,
+};
 use crate::middleware::AuthContext;
-use crate::models::SubscriptionChanges;
+use crate::models::{RejectedSubscriptionChange, SubscriptionChanges};
 use crate::state::AppState;
 
 #[derive(Debug, Serialize)]
@@ -18,12 +21,16 @@ pub struct SubscriptionListResponse {
     pub timestamp: i64,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub update_urls: Vec<[String; 2]>,
+    /// Uploaded changes that lost against newer changes of other devices
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<RejectedSubscriptionChange>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SubscriptionUploadRequest {
     pub add: Option<Vec<String>>,
     pub remove: Option<Vec<String>>,
+    /// When the changes were made on the device, defaults to now
     pub timestamp: Option<i64>,
 }
 
@@ -32,29 +39,26 @@ pub struct SubscriptionQueryParams {
     pub since: Option<i64>,
 }
 
-fn to_opml(subscriptions: &[String]) -> String {
-    let mut opml = String::from(
-        r#"<?xml version="1.0" encoding="UTF-8"?>
-<opml version="2.0">
-  <head>
-    <title>Podcast Subscriptions</title>
-  </head>
-  <body>
-"#,
-    );
-    for url in subscriptions {
-        opml.push_str(&format!(
-            r#"    <outline type="rss" text="{}" xmlUrl="{}"/>"#,
-            url, url
-        ));
-        opml.push('\n');
+/// Subscribed feeds in a simple API format; JSON lists only the URLs, the
+/// other formats are titled from the podcast directory
+async fn subscription_list_reply(
+    state: &AppState,
+    format: &Format,
+    subscriptions: &[String],
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    if let Format::Json | Format::Jsonp(_) = format {
+        return Ok(json_reply(format, &subscriptions));
     }
-    opml.push_str("  </body>\n</opml>");
-    opml
-}
 
-fn to_txt(subscriptions: &[String]) -> String {
-    subscriptions.join("\n")
+    let podcasts = state
+        .podcast_service
+        .get_metadata_for_urls(subscriptions)
+        .await?;
+    Ok(podcast_list_reply(
+        format,
+        "Podcast Subscriptions",
+        &podcasts,
+    ))
 }
 
 pub async fn get_subscriptions(
@@ -73,8 +77,13 @@ pub async fn get_subscriptions(
         .get_or_create_device(auth.user_id, &device_id, None, None)
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
+    state
+        .device_service
+        .record_seen(db_device_id, auth.user_agent.as_deref())
+        .await?;
 
     if let Some(since) = params.since {
+        let cursor = state.change_log_service.current_cursor().await?;
         let (add, remove) = state
             .subscription_service
             .get_changes_since(auth.user_id, db_device_id, since)
@@ -84,8 +93,9 @@ pub async fn get_subscriptions(
         Ok(json(&SubscriptionListResponse {
             add,
             remove,
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp: cursor,
             update_urls: vec![],
+            rejected: vec![],
         }))
     } else {
         let subscriptions = state
@@ -114,16 +124,34 @@ pub async fn upload_subscriptions(
         .get_or_create_device(auth.user_id, &device_id, None, None)
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
+    state
+        .device_service
+        .record_seen(db_device_id, auth.user_agent.as_deref())
+        .await?;
 
     // Sanitize URLs
-    let (sanitized_add, mut add_updates) =
-        crate::utils::sanitize_urls(req.add.as_deref().unwrap_or_default());
-    let (sanitized_remove, mut remove_updates) =
-        crate::utils::sanitize_urls(req.remove.as_deref().unwrap_or_default());
+    let (sanitized_add, mut add_updates) = state
+        .url_service
+        .sanitize_urls(req.add.as_deref().unwrap_or_default())
+        .await?;
+    let (sanitized_remove, mut remove_updates) = state
+        .url_service
+        .sanitize_urls(req.remove.as_deref().unwrap_or_default())
+        .await?;
+
+    // Invalid URLs are blanked out; the client learns that from `update_urls`
+    let sanitized_add: Vec<String> = sanitized_add
+        .into_iter()
+        .filter(|url| !url.is_empty())
+        .collect();
+    let sanitized_remove: Vec<String> = sanitized_remove
+        .into_iter()
+        .filter(|url| !url.is_empty())
+        .collect();
 
     // Check for conflicts (same URL in both add and remove)
     for add_url in &sanitized_add {
-        if !add_url.is_empty() && sanitized_remove.contains(add_url) {
+        if sanitized_remove.contains(add_url) {
             return Err(reject::custom(AppError::BadRequest(format!(
                 "URL cannot be both added and removed: {}",
                 add_url
@@ -144,7 +172,8 @@ pub async fn upload_subscriptions(
             .unwrap_or_else(|| chrono::Utc::now().timestamp()),
     };
 
-    state
+    let cursor = state.change_log_service.current_cursor().await?;
+    let rejected = state
         .subscription_service
         .upload_changes(auth.user_id, db_device_id, changes)
         .await
@@ -153,27 +182,64 @@ pub async fn upload_subscriptions(
     Ok(json(&SubscriptionListResponse {
         add: vec![],
         remove: vec![],
-        timestamp: chrono::Utc::now().timestamp(),
+        timestamp: cursor,
         update_urls: all_updates,
+        rejected,
     }))
 }
 
+/// Full add/remove history of one device
+pub async fn get_subscription_history(
+    username: String,
+    device_id: String,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    if username != auth.username {
+        return Err(reject::custom(AppError::Authorization));
+    }
+
+    let device = state
+        .device_service
+        .find_by_device_id(auth.user_id, &device_id)
+        .await
+        .map_err(reject::custom)?;
+    state
+        .device_service
+        .record_seen(device.id, auth.user_agent.as_deref())
+        .await?;
+
+    let history = state
+        .subscription_service
+        .get_history(auth.user_id, device.id)
+        .await
+        .map_err(reject::custom)?;
+
+    Ok(json(&history))
+}
+
 pub async fn get_subscriptions_simple(
     username: String,
     device_id: String,
     format: String,
+    params: FormatQueryParams,
     auth: AuthContext,
     state: AppState,
 ) -> Result<Box<dyn Reply + Send>, Rejection> {
     if username != auth.username {
         return Err(reject::custom(AppError::Authorization));
     }
+    let format = Format::parse(&format, params)?;
 
     let db_device_id = state
         .device_service
         .get_or_create_device(auth.user_id, &device_id, None, None)
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
+    state
+        .device_service
+        .record_seen(db_device_id, auth.user_agent.as_deref())
+        .await?;
 
     let subscriptions = state
         .subscription_service
@@ -181,36 +247,20 @@ pub async fn get_subscriptions_simple(
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
 
-    let reply: Box<dyn Reply + Send> = match format.as_str() {
-        "json" => Box::new(json(&subscriptions)),
-        "opml" => Box::new(reply::with_header(
-            to_opml(&subscriptions),
-            "content-type",
-            "text/xml",
-        )),
-        "txt" => Box::new(reply::with_header(
-            to_txt(&subscriptions),
-            "content-type",
-            "text/plain",
-        )),
-        _ => {
-            let msg = format!("Invalid format: {}", format);
-            return Err(reject::custom(AppError::Internal(msg)));
-        }
-    };
-
-    Ok(reply)
+    subscription_list_reply(&state, &format, &subscriptions).await
 }
 
 pub async fn get_all_subscriptions_simple(
     username: String,
     format: String,
+    params: FormatQueryParams,
     auth: AuthContext,
     state: AppState,
 ) -> Result<Box<dyn Reply + Send>, Rejection> {
     if username != auth.username {
         return Err(reject::custom(AppError::Authorization));
     }
+    let format = Format::parse(&format, params)?;
 
     let subscriptions = state
         .subscription_service
@@ -218,25 +268,7 @@ pub async fn get_all_subscriptions_simple(
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
 
-    let reply: Box<dyn Reply + Send> = match format.as_str() {
-        "json" => Box::new(json(&subscriptions)),
-        "opml" => Box::new(reply::with_header(
-            to_opml(&subscriptions),
-            "content-type",
-            "text/xml",
-        )),
-        "txt" => Box::new(reply::with_header(
-            to_txt(&subscriptions),
-            "content-type",
-            "text/plain",
-        )),
-        _ => {
-            let msg = format!("Invalid format: {}", format);
-            return Err(reject::custom(AppError::Internal(msg)));
-        }
-    };
-
-    Ok(reply)
+    subscription_list_reply(&state, &format, &subscriptions).await
 }
 
 pub async fn upload_subscriptions_simple(
@@ -256,40 +288,18 @@ pub async fn upload_subscriptions_simple(
         .get_or_create_device(auth.user_id, &device_id, None, None)
         .await
         .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
+    state
+        .device_service
+        .record_seen(db_device_id, auth.user_agent.as_deref())
+        .await?;
 
-    let body_str = String::from_utf8(body.to_vec())
-        .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
+    let podcast_urls = parse_podcast_list(&format, &body)?;
 
-    let podcast_urls = match format.as_str() {
-        "json" => {
-            let urls: Vec<String> = serde_json::from_str(&body_str)
-                .map_err(|e| reject::custom(AppError::Internal(e.to_string())))?;
-            urls
-        }
-        "txt" => body_str
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| line.trim().to_string())
-            .collect(),
-        "opml" => {
-            let urls: Vec<String> = body_str
-                .lines()
-                .filter_map(|line| {
-                    if let Some(start) = line.find("xmlUrl=\"") {
-                        if let Some(end) = line[start + 8..].find('"') {
-                            return Some(line[start + 8..start + 8 + end].to_string());
-                        }
-                    }
-                    None
-                })
-                .collect();
-            urls
-        }
-        _ => {
-            let msg = format!("Invalid format: {}", format);
-            return Err(reject::custom(AppError::Internal(msg)));
-        }
-    };
+    let (podcast_urls, _) = state.url_service.sanitize_urls(&podcast_urls).await?;
+    let podcast_urls = podcast_urls
+        .into_iter()
+        .filter(|url| !url.is_empty())
+        .collect();
 
     state
         .subscription_service
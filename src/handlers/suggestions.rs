@@ -0,0 +1,25 @@
+use warp::{Rejection, Reply};
+
+use crate::handlers::directory::{parse_count, split_format};
+use crate::handlers::format::{podcast_list_reply, Format, FormatQueryParams};
+use crate::middleware::AuthContext;
+use crate::state::AppState;
+
+/// `/suggestions/{count}.{format}`
+pub async fn get_suggestions(
+    count_with_format: String,
+    params: FormatQueryParams,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<Box<dyn Reply + Send>, Rejection> {
+    let (count, format) = split_format(&count_with_format);
+    let format = Format::parse(format, params)?;
+    let count = parse_count(count)?;
+
+    let podcasts = state
+        .podcast_service
+        .suggestions(auth.user_id, count)
+        .await?;
+
+    Ok(podcast_list_reply(&format, "Suggestions", &podcasts))
+}
@@ -0,0 +1,173 @@
+use serde::Deserialize;
+use warp::{http::StatusCode, reject, reply, reply::json, Rejection, Reply};
+
+use crate::error::AppError;
+use crate::middleware::AuthContext;
+use crate::models::{User, UserResponse};
+use crate::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub is_admin: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub password: String,
+}
+
+async fn find_user(state: &AppState, username: &str) -> Result<User, Rejection> {
+    state
+        .user_service
+        .find_by_username(username)
+        .await
+        .map_err(reject::custom)?
+        .ok_or_else(|| reject::custom(AppError::NotFound(format!("User '{}' not found", username))))
+}
+
+/// Administrators must not lock themselves out
+fn ensure_not_self(auth: &AuthContext, user: &User, operation: &str) -> Result<(), Rejection> {
+    if auth.user_id == user.id {
+        return Err(reject::custom(AppError::BadRequest(format!(
+            "Administrators cannot {} their own account",
+            operation
+        ))));
+    }
+    Ok(())
+}
+
+/// GET /api/admin/users
+pub async fn list_users(auth: AuthContext, state: AppState) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let users: Vec<UserResponse> = state
+        .user_service
+        .list_users()
+        .await
+        .map_err(reject::custom)?
+        .iter()
+        .map(User::to_response)
+        .collect();
+
+    Ok(json(&users))
+}
+
+/// POST /api/admin/users
+pub async fn create_user(
+    auth: AuthContext,
+    state: AppState,
+    req: CreateUserRequest,
+) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let user = state
+        .user_service
+        .register_user(req.username.trim(), &req.password, req.is_admin)
+        .await
+        .map_err(reject::custom)?;
+
+    tracing::info!("Admin {} created user {}", auth.username, user.username);
+
+    Ok(reply::with_status(
+        json(&user.to_response()),
+        StatusCode::CREATED,
+    ))
+}
+
+/// GET /api/admin/users/{username}
+pub async fn get_user(
+    username: String,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let user = find_user(&state, &username).await?;
+    Ok(json(&user.to_response()))
+}
+
+/// DELETE /api/admin/users/{username}
+pub async fn delete_user(
+    username: String,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let user = find_user(&state, &username).await?;
+    ensure_not_self(&auth, &user, "delete")?;
+
+    state
+        .user_service
+        .delete_user(user.id)
+        .await
+        .map_err(reject::custom)?;
+
+    tracing::info!("Admin {} deleted user {}", auth.username, user.username);
+
+    Ok(json(&serde_json::json!({ "status": "ok" })))
+}
+
+/// POST /api/admin/users/{username}/disable and /enable
+pub async fn set_user_disabled(
+    username: String,
+    disabled: bool,
+    auth: AuthContext,
+    state: AppState,
+) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let user = find_user(&state, &username).await?;
+    ensure_not_self(&auth, &user, "disable")?;
+
+    state
+        .user_service
+        .set_disabled(user.id, disabled)
+        .await
+        .map_err(reject::custom)?;
+
+    if disabled {
+        state
+            .session_service
+            .delete_user_sessions(user.id)
+            .await
+            .map_err(reject::custom)?;
+        state
+            .admin_session_service
+            .delete_user_sessions(user.id)
+            .await
+            .map_err(reject::custom)?;
+    }
+
+    let user = find_user(&state, &username).await?;
+    Ok(json(&user.to_response()))
+}
+
+/// POST /api/admin/users/{username}/password
+pub async fn reset_password(
+    username: String,
+    auth: AuthContext,
+    state: AppState,
+    req: ResetPasswordRequest,
+) -> Result<impl Reply, Rejection> {
+    auth.require_admin().map_err(reject::custom)?;
+
+    let user = find_user(&state, &username).await?;
+
+    state
+        .user_service
+        .reset_password(user.id, &req.password)
+        .await
+        .map_err(reject::custom)?;
+
+    tracing::info!(
+        "Admin {} reset the password of user {}",
+        auth.username,
+        user.username
+    );
+
+    Ok(json(&serde_json::json!({ "status": "ok" })))
+}
@@ -11,10 +11,13 @@ mod utils;
 
 use sqlx::SqlitePool;
 use std::path::Path;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::config::Config;
 use crate::middleware::AuthService;
+use crate::repository::{EpisodeRepository, PodcastRepository, UrlRepository};
+use crate::services::{FeedFetcher, UrlService};
 use crate::state::AppState;
 
 #[tokio::main]
@@ -31,12 +34,13 @@ async fn main() -> anyhow::Result<()> {
 
     ensure_database_exists(&config.db_path);
     let pool = create_database_pool(&config.db_path).await?;
-    run_migrations(&pool).await?;
+    run_migrations(&pool, &config.tracking_params).await?;
 
     let state = AppState::new(pool.clone(), config.clone());
     let auth_service = AuthService::new(state.user_service.clone(), state.session_service.clone());
 
     initialize_admin_user(&state, &config).await?;
+    start_background_tasks(&pool, &state, &config);
 
     let routes = create_app(auth_service, state, config.clone());
 
@@ -72,26 +76,94 @@ async fn create_database_pool(db_path: &str) -> anyhow::Result<SqlitePool> {
     Ok(pool)
 }
 
-async fn run_migrations(pool: &SqlitePool) -> anyhow::Result<()> {
+/// A schema change, or a data migration that needs more than SQL
+enum Migration {
+    Sql(&'static str),
+    /// Merges URLs stored before podcast and episode URLs were normalized
+    NormalizeUrls,
+}
+
+async fn run_migrations(pool: &SqlitePool, tracking_params: &[String]) -> anyhow::Result<()> {
+    use Migration::{NormalizeUrls, Sql};
+
     let migrations = [
-        include_str!("../migrations/001_initial.sql"),
-        include_str!("../migrations/002_add_settings.sql"),
-        include_str!("../migrations/003_sessions.sql"),
-        include_str!("../migrations/004_device_sync.sql"),
-        include_str!("../migrations/005_favorites.sql"),
-        include_str!("../migrations/006_podcasts_metadata.sql"),
+        Sql(include_str!("../migrations/001_initial.sql")),
+        Sql(include_str!("../migrations/002_add_settings.sql")),
+        Sql(include_str!("../migrations/003_sessions.sql")),
+        Sql(include_str!("../migrations/004_device_sync.sql")),
+        Sql(include_str!("../migrations/005_favorites.sql")),
+        Sql(include_str!("../migrations/006_podcasts_metadata.sql")),
+        Sql(include_str!("../migrations/007_episode_actions_latest.sql")),
+        Sql(include_str!("../migrations/008_user_disabled.sql")),
+        Sql(include_str!("../migrations/009_feed_fetch_state.sql")),
+        Sql(include_str!("../migrations/010_episodes.sql")),
+        Sql(include_str!("../migrations/011_podcast_tags.sql")),
+        Sql(include_str!("../migrations/012_search_index.sql")),
+        Sql(include_str!("../migrations/013_podcast_similarity.sql")),
+        Sql(include_str!("../migrations/014_episode_action_guid.sql")),
+        Sql(include_str!("../migrations/015_episode_action_dedup.sql")),
+        Sql(include_str!("../migrations/016_change_log.sql")),
+        Sql(include_str!("../migrations/017_subscription_events.sql")),
+        Sql(include_str!("../migrations/018_podcast_aliases.sql")),
+        Sql(include_str!("../migrations/019_podcast_lists.sql")),
+        Sql(include_str!("../migrations/020_chapters.sql")),
+        Sql(include_str!("../migrations/021_bookmarks.sql")),
+        Sql(include_str!("../migrations/022_device_last_seen.sql")),
+        // Version 23, which therefore has no file in `migrations/`. Versions
+        // are array positions: never insert an entry before the last one.
+        NormalizeUrls,
+        Sql(include_str!("../migrations/024_chapter_tombstones.sql")),
     ];
 
     tracing::info!("Running database migrations");
 
-    for (i, migration_sql) in migrations.iter().enumerate() {
-        tracing::info!("Running migration {}", i + 1);
-        for statement in migration_sql.split(';') {
-            let statement = statement.trim();
-            if !statement.is_empty() {
-                sqlx::query(statement).execute(pool).await?;
-            }
+    // Applied migrations are recorded so that non-idempotent statements
+    // (e.g. `ALTER TABLE ... ADD COLUMN`) only ever run once
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            applied_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    for (i, migration) in migrations.iter().enumerate() {
+        let version = (i + 1) as i64;
+
+        let applied = sqlx::query("SELECT 1 FROM schema_migrations WHERE version = ?")
+            .bind(version)
+            .fetch_optional(pool)
+            .await?
+            .is_some();
+        if applied {
+            continue;
+        }
+
+        tracing::info!("Running migration {}", version);
+        // URLs are rewritten in transactions of their own, before the
+        // migration is recorded; repeating the rewrite after an interruption
+        // is harmless
+        if let NormalizeUrls = migration {
+            let url_service =
+                UrlService::new(UrlRepository::new(pool.clone()), tracking_params.to_vec());
+            let rewritten = url_service.normalize_stored_urls().await?;
+            tracing::info!("Normalized {} stored podcast and episode URLs", rewritten);
         }
+
+        let mut tx = pool.begin().await?;
+        if let Sql(migration_sql) = migration {
+            // Executed as a single script so that trigger bodies (which
+            // contain their own `;`-terminated statements) are not split apart
+            sqlx::raw_sql(migration_sql).execute(&mut *tx).await?;
+        }
+        sqlx::query("INSERT INTO schema_migrations (version) VALUES (?)")
+            .bind(version)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
     }
 
     tracing::info!("Database migrations completed successfully");
@@ -123,6 +195,51 @@ async fn initialize_admin_user(state: &AppState, config: &Config) -> anyhow::Res
     Ok(())
 }
 
+fn start_background_tasks(pool: &SqlitePool, state: &AppState, config: &Config) {
+    if config.feed_refresh_interval > 0 {
+        let fetcher = FeedFetcher::new(
+            PodcastRepository::new(pool.clone()),
+            EpisodeRepository::new(pool.clone()),
+            UrlService::new(
+                UrlRepository::new(pool.clone()),
+                config.tracking_params.clone(),
+            ),
+            Duration::from_secs(config.feed_refresh_interval),
+        );
+        tokio::spawn(fetcher.run());
+        tracing::info!(
+            "Feed fetcher refreshes feeds every {} seconds",
+            config.feed_refresh_interval
+        );
+    } else {
+        tracing::info!("Feed fetcher is disabled");
+    }
+
+    if config.suggestion_refresh_interval > 0 {
+        tokio::spawn(
+            state
+                .podcast_service
+                .clone()
+                .refresh_suggestions(Duration::from_secs(config.suggestion_refresh_interval)),
+        );
+    } else {
+        tracing::info!("Podcast suggestions are disabled");
+    }
+
+    if config.device_retention_days > 0 {
+        tokio::spawn(
+            state
+                .device_service
+                .clone()
+                .archive_stale_devices(config.device_retention_days),
+        );
+        tracing::info!(
+            "Devices unseen for {} days are archived",
+            config.device_retention_days
+        );
+    }
+}
+
 fn create_app(
     auth_service: AuthService,
     state: AppState,
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use warp::{Filter, Rejection};
+
+use crate::services::AdminSessionService;
+
+/// Name of the cookie carrying the admin web UI session
+pub const ADMIN_SESSION_COOKIE: &str = "admin_session";
+
+#[derive(Clone, Debug)]
+pub struct AdminContext {
+    pub user_id: i64,
+    pub username: String,
+    pub session_id: String,
+}
+
+pub fn extract_admin_session_from_cookie(cookie_header: &str) -> Option<String> {
+    for cookie in cookie_header.split(';') {
+        let parts: Vec<&str> = cookie.trim().splitn(2, '=').collect();
+        if parts.len() == 2 && parts[0] == ADMIN_SESSION_COOKIE && !parts[1].is_empty() {
+            return Some(parts[1].to_string());
+        }
+    }
+    None
+}
+
+/// Resolves the admin session cookie to an `AdminContext`.
+///
+/// Never rejects on a missing or invalid session: pages decide themselves whether to redirect to the login
+/// form when no valid session is present.
+pub fn with_admin_session(
+    admin_session_service: Arc<AdminSessionService>,
+) -> impl Filter<Extract = (Option<AdminContext>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("cookie").and_then(move |cookie_header: Option<String>| {
+        let admin_session_service = admin_session_service.clone();
+        async move {
+            let Some(session_id) = cookie_header
+                .as_deref()
+                .and_then(extract_admin_session_from_cookie)
+            else {
+                return Ok::<_, Rejection>(None);
+            };
+
+            match admin_session_service.validate_session(&session_id).await {
+                Ok(user) => Ok(Some(AdminContext {
+                    user_id: user.id,
+                    username: user.username,
+                    session_id,
+                })),
+                Err(e) => {
+                    tracing::debug!("Admin session validation failed: {:?}", e);
+                    Ok(None)
+                }
+            }
+        }
+    })
+}
@@ -8,6 +8,32 @@ use crate::error::{AppError, AppResult};
 pub struct AuthContext {
     pub user_id: i64,
     pub username: String,
+    pub is_admin: bool,
+    /// `User-Agent` header of the request
+    pub user_agent: Option<String>,
+}
+
+impl AuthContext {
+    /// Fails with `AppError::Authorization` unless the user is an administrator
+    pub fn require_admin(&self) -> AppResult<()> {
+        if self.is_admin {
+            Ok(())
+        } else {
+            tracing::warn!("User {} attempted an admin-only operation", self.username);
+            Err(AppError::Authorization)
+        }
+    }
+}
+
+impl From<crate::models::User> for AuthContext {
+    fn from(user: crate::models::User) -> Self {
+        Self {
+            user_id: user.id,
+            username: user.username,
+            is_admin: user.is_admin,
+            user_agent: None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -27,7 +53,11 @@ impl AuthService {
         }
     }
 
-    pub async fn verify_credentials(&self, username: &str, password: &str) -> AppResult<i64> {
+    pub async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> AppResult<crate::models::User> {
         self.user_service
             .verify_credentials(username, password)
             .await
@@ -37,13 +67,18 @@ impl AuthService {
         self.session_service.validate_session(session_id).await
     }
 
-    pub async fn get_username_by_id(&self, user_id: i64) -> AppResult<String> {
+    pub async fn get_user_by_id(&self, user_id: i64) -> AppResult<crate::models::User> {
         let user = self
             .user_service
             .find_by_id(user_id)
             .await?
             .ok_or(AppError::Authentication)?;
-        Ok(user.username)
+
+        if user.disabled {
+            return Err(AppError::Authentication);
+        }
+
+        Ok(user)
     }
 }
 
@@ -60,6 +95,14 @@ fn extract_session_from_cookie(cookie_header: &str) -> Option<String> {
 
 pub fn with_auth(
     auth_service: AuthService,
+) -> impl Filter<Extract = (AuthContext,), Error = Rejection> + Clone {
+    authenticate(auth_service)
+        .and(warp::header::optional::<String>("user-agent"))
+        .map(|auth: AuthContext, user_agent: Option<String>| AuthContext { user_agent, ..auth })
+}
+
+fn authenticate(
+    auth_service: AuthService,
 ) -> impl Filter<Extract = (AuthContext,), Error = Rejection> + Clone {
     warp::header::optional::<String>("cookie")
         .and(warp::header::optional::<String>("authorization"))
@@ -74,21 +117,19 @@ pub fn with_auth(
                         if let Some(session_id) = extract_session_from_cookie(&cookie) {
                             tracing::debug!("Attempting cookie authentication");
                             match auth_service.verify_session(&session_id).await {
-                                Ok(user_id) => {
-                                    match auth_service.get_username_by_id(user_id).await {
-                                        Ok(username) => {
-                                            tracing::info!(
-                                                "Cookie auth successful for user: {} (id: {})",
-                                                username,
-                                                user_id
-                                            );
-                                            return Ok(AuthContext { user_id, username });
-                                        }
-                                        Err(e) => {
-                                            tracing::warn!("Failed to get username: {:?}", e);
-                                        }
+                                Ok(user_id) => match auth_service.get_user_by_id(user_id).await {
+                                    Ok(user) => {
+                                        tracing::info!(
+                                            "Cookie auth successful for user: {} (id: {})",
+                                            user.username,
+                                            user_id
+                                        );
+                                        return Ok(AuthContext::from(user));
                                     }
-                                }
+                                    Err(e) => {
+                                        tracing::warn!("Failed to get user: {:?}", e);
+                                    }
+                                },
                                 Err(e) => {
                                     tracing::debug!("Session validation failed: {:?}", e);
                                 }
@@ -124,7 +165,7 @@ pub fn with_auth(
 
                     tracing::info!("Verifying credentials for user: {}", username);
 
-                    let user_id = auth_service
+                    let user = auth_service
                         .verify_credentials(&username, &password)
                         .await
                         .map_err(|e| {
@@ -132,8 +173,8 @@ pub fn with_auth(
                             warp::reject::custom(e)
                         })?;
 
-                    tracing::info!("Auth successful for user: {} (id: {})", username, user_id);
-                    Ok(AuthContext { user_id, username })
+                    tracing::info!("Auth successful for user: {} (id: {})", username, user.id);
+                    Ok(AuthContext::from(user))
                 }
             },
         )
@@ -1,3 +1,5 @@
+pub mod admin;
 pub mod auth;
 
+pub use admin::{with_admin_session, AdminContext};
 pub use auth::{with_auth, AuthContext, AuthService};
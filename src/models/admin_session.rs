@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AdminSession {
+    pub id: String,
+    pub user_id: i64,
+    pub expires_at: i64,
+    pub created_at: i64,
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Bookmark {
+    pub id: i64,
+    pub user_id: i64,
+    pub podcast_url: String,
+    pub episode_url: String,
+    pub position: i64,
+    pub note: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Set once the bookmark was deleted
+    pub deleted_at: Option<i64>,
+}
+
+/// Position and note of a bookmark, as stored in the `bookmarks` episode
+/// setting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookmarkMark {
+    pub position: i64,
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Bookmark as returned by `/api/2/bookmarks`
+#[derive(Debug, Serialize)]
+pub struct BookmarkResponse {
+    pub id: i64,
+    pub podcast: String,
+    pub episode: String,
+    pub position: i64,
+    pub note: String,
+    pub created: i64,
+    pub updated: i64,
+}
+
+impl Bookmark {
+    pub fn to_response(&self) -> BookmarkResponse {
+        BookmarkResponse {
+            id: self.id,
+            podcast: self.podcast_url.clone(),
+            episode: self.episode_url.clone(),
+            position: self.position,
+            note: self.note.clone(),
+            created: self.created_at,
+            updated: self.updated_at,
+        }
+    }
+
+    pub fn to_mark(&self) -> BookmarkMark {
+        BookmarkMark {
+            position: self.position,
+            note: self.note.clone(),
+        }
+    }
+}
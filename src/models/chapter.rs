@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Chapter {
+    pub id: i64,
+    pub user_id: i64,
+    pub podcast_url: String,
+    pub episode_url: String,
+    pub start: i64,
+    pub end: i64,
+    pub label: Option<String>,
+    pub advertisement: bool,
+    pub created_at: i64,
+    pub deleted_at: Option<i64>,
+}
+
+/// Chapter as uploaded in `chapters_add`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterMark {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub advertisement: bool,
+}
+
+/// Time range of a chapter, as uploaded in `chapters_remove`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ChapterRange {
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Entry of the `chapters` list of `GET /api/2/chapters/{username}.json`
+#[derive(Debug, Serialize)]
+pub struct ChapterResponse {
+    pub podcast: String,
+    pub episode: String,
+    #[serde(flatten)]
+    pub mark: ChapterMark,
+    /// When the chapter was first uploaded
+    pub timestamp: i64,
+}
+
+/// Entry of the `removed` list of `GET /api/2/chapters/{username}.json`
+#[derive(Debug, Serialize)]
+pub struct RemovedChapterResponse {
+    pub podcast: String,
+    pub episode: String,
+    #[serde(flatten)]
+    pub range: ChapterRange,
+}
+
+impl Chapter {
+    pub fn to_response(&self) -> ChapterResponse {
+        ChapterResponse {
+            podcast: self.podcast_url.clone(),
+            episode: self.episode_url.clone(),
+            mark: ChapterMark {
+                start: self.start,
+                end: self.end,
+                label: self.label.clone(),
+                advertisement: self.advertisement,
+            },
+            timestamp: self.created_at,
+        }
+    }
+
+    pub fn to_removed_response(&self) -> RemovedChapterResponse {
+        RemovedChapterResponse {
+            podcast: self.podcast_url.clone(),
+            episode: self.episode_url.clone(),
+            range: ChapterRange {
+                start: self.start,
+                end: self.end,
+            },
+        }
+    }
+}
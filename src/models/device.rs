@@ -9,4 +9,10 @@ pub struct Device {
     pub r#type: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Last sync request naming the device
+    pub last_seen_at: Option<i64>,
+    /// User agent of that request
+    pub user_agent: Option<String>,
+    /// Set while the device is archived for inactivity
+    pub archived_at: Option<i64>,
 }
@@ -0,0 +1,91 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// Catalogued episode together with the podcast it belongs to
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Episode {
+    pub id: i64,
+    pub podcast_id: i64,
+    pub podcast_url: String,
+    pub podcast_title: Option<String>,
+    pub guid: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub duration: Option<i64>,
+    pub released: Option<i64>,
+}
+
+/// Episode data as found in a feed
+#[derive(Debug, Clone, Default)]
+pub struct EpisodeMetadata<'a> {
+    pub guid: &'a str,
+    pub url: &'a str,
+    pub title: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub website: Option<&'a str>,
+    pub duration: Option<i64>,
+    pub released: Option<i64>,
+}
+
+/// Episode in the format of the gpodder directory API
+#[derive(Debug, Clone, Serialize)]
+pub struct EpisodeData {
+    pub title: String,
+    pub url: String,
+    pub podcast_title: String,
+    pub podcast_url: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    pub mygpo_link: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub released: Option<String>,
+}
+
+/// Entry of the `updates` list of `/api/2/updates`
+#[derive(Debug, Clone, Serialize)]
+pub struct EpisodeUpdate {
+    #[serde(flatten)]
+    pub episode: EpisodeData,
+    /// `new` or the type of the user's latest action on the episode
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<serde_json::Value>,
+}
+
+/// Link to the directory page of an episode, see `/episode/{url}`
+pub fn episode_link(base_url: &str, episode_url: &str) -> String {
+    format!("{}/episode/{}", base_url, urlencoding::encode(episode_url))
+}
+
+impl Episode {
+    /// Release date in the `YYYY-MM-DDTHH:MM:SS` format used by the gpodder API
+    pub fn released_iso(&self) -> Option<String> {
+        self.released
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+            .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S").to_string())
+    }
+
+    pub fn to_data(&self, base_url: &str) -> EpisodeData {
+        EpisodeData {
+            title: self.title.clone().unwrap_or_default(),
+            url: self.url.clone(),
+            podcast_title: self.podcast_title.clone().unwrap_or_default(),
+            podcast_url: self.podcast_url.clone(),
+            description: self.description.clone().unwrap_or_default(),
+            website: self.website.clone(),
+            mygpo_link: episode_link(base_url, &self.url),
+            released: self.released_iso(),
+        }
+    }
+
+    pub fn to_update(&self, base_url: &str) -> EpisodeUpdate {
+        EpisodeUpdate {
+            episode: self.to_data(base_url),
+            status: "new".to_string(),
+            action: None,
+        }
+    }
+}
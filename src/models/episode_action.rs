@@ -8,6 +8,7 @@ pub struct EpisodeAction {
     pub device_id: i64,
     pub podcast_url: String,
     pub episode_url: String,
+    pub guid: Option<String>,
     pub action: String,
     pub timestamp: i64,
     pub started: Option<i64>,
@@ -18,6 +19,7 @@ pub struct EpisodeAction {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EpisodeActionQuery {
+    /// Change cursor, not a client timestamp
     pub since: Option<i64>,
     pub podcast: Option<String>,
     pub device: Option<String>,
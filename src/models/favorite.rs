@@ -43,6 +43,27 @@ pub struct FavoriteResponse {
     pub mygpo_link: String,
 }
 
+impl FavoriteResponse {
+    /// Fill in whatever the client did not send from the episode catalogue
+    pub fn fill_from(&mut self, episode: &crate::models::Episode) {
+        if self.title.is_empty() {
+            self.title = episode.title.clone().unwrap_or_default();
+        }
+        if self.podcast_title.is_empty() {
+            self.podcast_title = episode.podcast_title.clone().unwrap_or_default();
+        }
+        if self.description.is_none() {
+            self.description = episode.description.clone();
+        }
+        if self.website.is_none() {
+            self.website = episode.website.clone();
+        }
+        if self.released.is_none() {
+            self.released = episode.released_iso();
+        }
+    }
+}
+
 impl FavoriteEpisode {
     pub fn to_response(&self, base_url: &str) -> FavoriteResponse {
         FavoriteResponse {
@@ -53,7 +74,7 @@ impl FavoriteEpisode {
             description: self.description.clone(),
             website: self.website.clone(),
             released: self.released.clone(),
-            mygpo_link: format!("{}/episode/{}", base_url, self.id),
+            mygpo_link: crate::models::episode_link(base_url, &self.episode_url),
         }
     }
 }
@@ -1,19 +1,29 @@
+pub mod admin_session;
+pub mod bookmark;
+pub mod chapter;
 pub mod device;
 pub mod device_sync;
+pub mod episode;
 pub mod episode_action;
 pub mod favorite;
 pub mod podcast;
+pub mod podcast_list;
 pub mod session;
 pub mod setting;
 pub mod subscription;
 pub mod user;
 
+pub use admin_session::AdminSession;
+pub use bookmark::{Bookmark, BookmarkMark, BookmarkResponse};
+pub use chapter::{Chapter, ChapterMark, ChapterRange, ChapterResponse, RemovedChapterResponse};
 pub use device::Device;
 pub use device_sync::{DeviceSyncRequest, DeviceSyncStatus};
+pub use episode::{episode_link, Episode, EpisodeData, EpisodeMetadata, EpisodeUpdate};
 pub use episode_action::{EpisodeAction, EpisodeActionQuery};
 pub use favorite::{FavoriteEpisode, FavoriteMetadata, FavoriteResponse};
-pub use podcast::{Podcast, PodcastMetadata};
+pub use podcast::{FeedFetchState, Podcast, PodcastAlias, PodcastMetadata, TagResponse};
+pub use podcast_list::{PodcastList, PodcastListResponse};
 pub use session::Session;
 pub use setting::{Setting, SettingRequest};
-pub use subscription::SubscriptionChanges;
-pub use user::User;
+pub use subscription::{RejectedSubscriptionChange, SubscriptionChanges, SubscriptionEvent};
+pub use user::{User, UserResponse};
@@ -14,6 +14,14 @@ pub struct Podcast {
     pub updated_at: i64,
 }
 
+/// Cache validators of the last successful feed fetch
+#[derive(Debug, Clone)]
+pub struct FeedFetchState {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PodcastMetadata {
     pub url: String,
@@ -27,6 +35,24 @@ pub struct PodcastMetadata {
     pub mygpo_link: String,
 }
 
+/// Old URL of a podcast that moved, see `UrlService::move_podcast`
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PodcastAlias {
+    pub alias_url: String,
+    pub podcast_url: String,
+    /// `redirect`, `new-feed-url` or `admin`
+    pub source: String,
+    pub created_at: i64,
+}
+
+/// Entry of `/api/2/tags/{count}.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct TagResponse {
+    pub title: String,
+    pub tag: String,
+    pub usage: i64,
+}
+
 impl Podcast {
     /// Convert to API response format with fallback values
     pub fn to_metadata(&self, base_url: &str) -> PodcastMetadata {
@@ -0,0 +1,36 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PodcastList {
+    pub id: i64,
+    pub user_id: i64,
+    /// URL-safe identifier derived from the title
+    pub name: String,
+    pub title: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Entry of `/api/2/lists/{username}.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PodcastListResponse {
+    pub title: String,
+    pub name: String,
+    pub web: String,
+}
+
+impl PodcastList {
+    pub fn to_response(&self, base_url: &str, username: &str) -> PodcastListResponse {
+        PodcastListResponse {
+            title: self.title.clone(),
+            name: self.name.clone(),
+            web: format!(
+                "{}/api/2/lists/{}/list/{}.opml",
+                base_url,
+                urlencoding::encode(username),
+                self.name
+            ),
+        }
+    }
+}
@@ -1,8 +1,30 @@
 use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubscriptionChanges {
     pub add: Vec<String>,
     pub remove: Vec<String>,
+    /// When the changes were made, decides conflicts between devices
+    pub timestamp: i64,
+}
+
+/// A change that lost against a newer conflicting change of the same podcast
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedSubscriptionChange {
+    pub podcast: String,
+    /// The rejected action, `add` or `remove`
+    pub action: String,
+    /// Time of the newer change that is kept
+    pub superseded_at: i64,
+}
+
+/// One add or remove in the subscription history of a device
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SubscriptionEvent {
+    #[serde(rename = "podcast")]
+    pub podcast_url: String,
+    /// `add` or `remove`
+    pub action: String,
     pub timestamp: i64,
 }
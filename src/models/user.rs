@@ -6,5 +6,26 @@ pub struct User {
     pub username: String,
     pub password_hash: String,
     pub is_admin: bool,
+    pub disabled: bool,
     pub created_at: i64,
 }
+
+/// Response format for the user management API
+#[derive(Debug, Serialize)]
+pub struct UserResponse {
+    pub username: String,
+    pub is_admin: bool,
+    pub disabled: bool,
+    pub created_at: i64,
+}
+
+impl User {
+    pub fn to_response(&self) -> UserResponse {
+        UserResponse {
+            username: self.username.clone(),
+            is_admin: self.is_admin,
+            disabled: self.disabled,
+            created_at: self.created_at,
+        }
+    }
+}
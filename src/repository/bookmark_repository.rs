@@ -0,0 +1,170 @@
+use sqlx::SqlitePool;
+
+use crate::models::{Bookmark, BookmarkMark};
+
+#[derive(Clone)]
+pub struct BookmarkRepository {
+    pool: SqlitePool,
+}
+
+impl BookmarkRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: i64,
+        podcast_url: &str,
+        episode_url: &str,
+        mark: &BookmarkMark,
+    ) -> Result<Bookmark, sqlx::Error> {
+        sqlx::query_as::<_, Bookmark>(
+            r#"
+            INSERT INTO bookmarks (user_id, podcast_url, episode_url, position, note)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING id, user_id, podcast_url, episode_url, position, note, created_at, updated_at, deleted_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(podcast_url)
+        .bind(episode_url)
+        .bind(mark.position)
+        .bind(&mark.note)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Change position and/or note of a bookmark that was not deleted
+    pub async fn update(
+        &self,
+        user_id: i64,
+        id: i64,
+        position: Option<i64>,
+        note: Option<&str>,
+    ) -> Result<Option<Bookmark>, sqlx::Error> {
+        sqlx::query_as::<_, Bookmark>(
+            r#"
+            UPDATE bookmarks
+            SET position = COALESCE(?, position),
+                note = COALESCE(?, note),
+                updated_at = strftime('%s', 'now')
+            WHERE user_id = ? AND id = ? AND deleted_at IS NULL
+            RETURNING id, user_id, podcast_url, episode_url, position, note, created_at, updated_at, deleted_at
+            "#,
+        )
+        .bind(position)
+        .bind(note)
+        .bind(user_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Mark a bookmark as deleted; returns false if there is none
+    pub async fn delete(&self, user_id: i64, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bookmarks
+            SET deleted_at = strftime('%s', 'now'), updated_at = strftime('%s', 'now')
+            WHERE user_id = ? AND id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Bookmarks created, changed or deleted after cursor `since`, optionally
+    /// of one podcast or episode only
+    pub async fn list_since(
+        &self,
+        user_id: i64,
+        since: i64,
+        podcast_url: Option<&str>,
+        episode_url: Option<&str>,
+    ) -> Result<Vec<Bookmark>, sqlx::Error> {
+        sqlx::query_as::<_, Bookmark>(
+            r#"
+            SELECT id, user_id, podcast_url, episode_url, position, note, created_at, updated_at, deleted_at
+            FROM bookmarks
+            WHERE user_id = ? AND change_seq > ?
+              AND (?3 IS NULL OR podcast_url = ?3)
+              AND (?4 IS NULL OR episode_url = ?4)
+            ORDER BY podcast_url, episode_url, position, id
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(podcast_url)
+        .bind(episode_url)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Make `marks` the bookmarks of an episode: bookmarks with the same
+    /// position and note are kept, the others deleted or created
+    pub async fn replace_episode(
+        &self,
+        user_id: i64,
+        podcast_url: &str,
+        episode_url: &str,
+        marks: &[BookmarkMark],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing = sqlx::query_as::<_, Bookmark>(
+            r#"
+            SELECT id, user_id, podcast_url, episode_url, position, note, created_at, updated_at, deleted_at
+            FROM bookmarks
+            WHERE user_id = ? AND episode_url = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(episode_url)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for bookmark in &existing {
+            if !marks.contains(&bookmark.to_mark()) {
+                sqlx::query(
+                    r#"
+                    UPDATE bookmarks
+                    SET deleted_at = strftime('%s', 'now'), updated_at = strftime('%s', 'now')
+                    WHERE id = ?
+                    "#,
+                )
+                .bind(bookmark.id)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        let mut kept: Vec<BookmarkMark> = existing.iter().map(Bookmark::to_mark).collect();
+        for mark in marks {
+            if kept.contains(mark) {
+                continue;
+            }
+            sqlx::query(
+                r#"
+                INSERT INTO bookmarks (user_id, podcast_url, episode_url, position, note)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(user_id)
+            .bind(podcast_url)
+            .bind(episode_url)
+            .bind(mark.position)
+            .bind(&mark.note)
+            .execute(&mut *tx)
+            .await?;
+            kept.push(mark.clone());
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use sqlx::{Row, SqlitePool};
+
+#[derive(Clone)]
+pub struct ChangeLogRepository {
+    pool: SqlitePool,
+}
+
+impl ChangeLogRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Sequence number of the most recent change. Every change written later
+    /// gets a higher number, so it is a loss-free `since` cursor.
+    pub async fn current_cursor(&self) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT COALESCE(
+                (SELECT seq FROM sqlite_sequence WHERE name = 'change_log'),
+                0
+            )
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get_unchecked::<i64, _>(0))
+    }
+}
@@ -0,0 +1,97 @@
+use sqlx::SqlitePool;
+
+use crate::models::{Chapter, ChapterMark, ChapterRange};
+
+#[derive(Clone)]
+pub struct ChapterRepository {
+    pool: SqlitePool,
+}
+
+impl ChapterRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Remove, then add or relabel chapters of one episode. Removed chapters
+    /// are kept as tombstones; adding one again revives it.
+    pub async fn apply_changes(
+        &self,
+        user_id: i64,
+        podcast_url: &str,
+        episode_url: &str,
+        add: &[ChapterMark],
+        remove: &[ChapterRange],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for range in remove {
+            sqlx::query(
+                r#"
+                UPDATE chapters
+                SET deleted_at = strftime('%s', 'now')
+                WHERE user_id = ? AND episode_url = ? AND start = ? AND "end" = ?
+                  AND deleted_at IS NULL
+                "#,
+            )
+            .bind(user_id)
+            .bind(episode_url)
+            .bind(range.start)
+            .bind(range.end)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for mark in add {
+            sqlx::query(
+                r#"
+                INSERT INTO chapters (user_id, podcast_url, episode_url, start, "end", label, advertisement)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(user_id, episode_url, start, "end") DO UPDATE SET
+                    podcast_url = excluded.podcast_url,
+                    label = excluded.label,
+                    advertisement = excluded.advertisement,
+                    deleted_at = NULL
+                "#,
+            )
+            .bind(user_id)
+            .bind(podcast_url)
+            .bind(episode_url)
+            .bind(mark.start)
+            .bind(mark.end)
+            .bind(&mark.label)
+            .bind(mark.advertisement)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Chapters added, changed or removed after cursor `since`, optionally of
+    /// one podcast or episode only
+    pub async fn list_since(
+        &self,
+        user_id: i64,
+        since: i64,
+        podcast_url: Option<&str>,
+        episode_url: Option<&str>,
+    ) -> Result<Vec<Chapter>, sqlx::Error> {
+        sqlx::query_as::<_, Chapter>(
+            r#"
+            SELECT id, user_id, podcast_url, episode_url, start, "end", label, advertisement, created_at, deleted_at
+            FROM chapters
+            WHERE user_id = ? AND change_seq > ?
+              AND (?3 IS NULL OR podcast_url = ?3)
+              AND (?4 IS NULL OR episode_url = ?4)
+            ORDER BY episode_url, start, "end"
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(podcast_url)
+        .bind(episode_url)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -1,5 +1,5 @@
 use crate::models::Device;
-use sqlx::{Error, Row, SqlitePool};
+use sqlx::{Error, Row, Sqlite, SqlitePool, Transaction};
 
 #[derive(Clone)]
 pub struct DeviceRepository {
@@ -44,7 +44,7 @@ impl DeviceRepository {
             r#"
             SELECT 
                 id, user_id, device_id, caption, type, 
-                created_at, updated_at
+                created_at, updated_at, last_seen_at, user_agent, archived_at
             FROM devices 
             WHERE user_id = ? AND device_id = ?
             "#,
@@ -62,21 +62,35 @@ impl DeviceRepository {
             r#type: row.get_unchecked::<Option<String>, _>(4),
             created_at: row.get_unchecked(5),
             updated_at: row.get_unchecked::<i64, _>(6),
+            last_seen_at: row.get_unchecked::<Option<i64>, _>(7),
+            user_agent: row.get_unchecked::<Option<String>, _>(8),
+            archived_at: row.get_unchecked::<Option<i64>, _>(9),
         }))
     }
 
+    /// Devices of a user, without the archived ones
     pub async fn list_by_user(&self, user_id: i64) -> Result<Vec<Device>, Error> {
+        self.list(user_id, false).await
+    }
+
+    /// Devices of a user, including the archived ones
+    pub async fn list_all_by_user(&self, user_id: i64) -> Result<Vec<Device>, Error> {
+        self.list(user_id, true).await
+    }
+
+    async fn list(&self, user_id: i64, include_archived: bool) -> Result<Vec<Device>, Error> {
         let rows = sqlx::query(
             r#"
             SELECT 
                 id, user_id, device_id, caption, type, 
-                created_at, updated_at
+                created_at, updated_at, last_seen_at, user_agent, archived_at
             FROM devices 
-            WHERE user_id = ?
+            WHERE user_id = ? AND (? OR archived_at IS NULL)
             ORDER BY created_at DESC
             "#,
         )
         .bind(user_id)
+        .bind(include_archived)
         .fetch_all(&self.pool)
         .await?;
 
@@ -90,7 +104,269 @@ impl DeviceRepository {
                 r#type: row.get_unchecked::<Option<String>, _>(4),
                 created_at: row.get_unchecked(5),
                 updated_at: row.get_unchecked::<i64, _>(6),
+                last_seen_at: row.get_unchecked::<Option<i64>, _>(7),
+                user_agent: row.get_unchecked::<Option<String>, _>(8),
+                archived_at: row.get_unchecked::<Option<i64>, _>(9),
             })
             .collect())
     }
+
+    /// Change caption and/or type of a device
+    pub async fn update(
+        &self,
+        id: i64,
+        caption: Option<&str>,
+        device_type: Option<&str>,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            UPDATE devices
+            SET caption = COALESCE(?, caption),
+                type = COALESCE(?, type),
+                updated_at = strftime('%s', 'now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(caption)
+        .bind(device_type)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a request of the device; a seen device is no longer archived
+    pub async fn mark_seen(&self, id: i64, user_agent: Option<&str>) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            UPDATE devices
+            SET last_seen_at = strftime('%s', 'now'),
+                user_agent = COALESCE(?, user_agent),
+                archived_at = NULL
+            WHERE id = ?
+            "#,
+        )
+        .bind(user_agent)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Archive devices not seen since `cutoff`; returns how many
+    pub async fn archive_unseen(&self, cutoff: i64) -> Result<u64, Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE devices
+            SET archived_at = strftime('%s', 'now')
+            WHERE archived_at IS NULL AND COALESCE(last_seen_at, updated_at) < ?
+            "#,
+        )
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Give a device a new device id, keeping everything stored for it
+    pub async fn rename(&self, id: i64, device_id: &str) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            UPDATE devices
+            SET device_id = ?, updated_at = strftime('%s', 'now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(device_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Delete a device with its subscriptions, history, episode actions and
+    /// settings, and take it out of its sync group
+    pub async fn delete(&self, user_id: i64, id: i64) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let podcast_urls = Self::subscribed_podcasts(&mut tx, id).await?;
+
+        sqlx::query("DELETE FROM devices WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::remove_single_device_groups(&mut tx, user_id).await?;
+        Self::recount_subscribers(&mut tx, &podcast_urls).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Move everything stored for device `source` to device `target` and
+    /// delete `source`. Subscriptions end up active if either device had
+    /// them active; where both have the same episode action or setting,
+    /// `target`'s copy is kept. `target` takes over the sync group of
+    /// `source`; if both are in different groups, the groups are merged.
+    /// Every device of the resulting group gets the union of the group's
+    /// subscriptions, as when devices are synchronized.
+    pub async fn merge(&self, user_id: i64, source: i64, target: i64) -> Result<(), Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let podcast_urls = Self::subscribed_podcasts(&mut tx, source).await?;
+
+        sqlx::query("UPDATE subscription_events SET device_id = ? WHERE device_id = ?")
+            .bind(target)
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET removed_at = NULL
+            WHERE device_id = ?1 AND removed_at IS NOT NULL
+              AND EXISTS (
+                SELECT 1 FROM subscriptions o
+                WHERE o.device_id = ?2 AND o.podcast_url = subscriptions.podcast_url
+                  AND o.removed_at IS NULL
+              )
+            "#,
+        )
+        .bind(target)
+        .bind(source)
+        .execute(&mut *tx)
+        .await?;
+
+        // Setting `added_at` logs the moved subscriptions as changes, so
+        // `target` receives them with its next update
+        sqlx::query(
+            r#"
+            UPDATE OR IGNORE subscriptions
+            SET device_id = ?, added_at = added_at
+            WHERE device_id = ?
+            "#,
+        )
+        .bind(target)
+        .bind(source)
+        .execute(&mut *tx)
+        .await?;
+
+        // The peers of `source` join the group of `target`; `source`'s own
+        // membership cannot move there and cascades
+        sqlx::query(
+            r#"
+            UPDATE device_sync_members
+            SET sync_group_id = (SELECT sync_group_id FROM device_sync_members WHERE device_id = ?2)
+            WHERE sync_group_id = (SELECT sync_group_id FROM device_sync_members WHERE device_id = ?1)
+              AND device_id != ?1
+              AND EXISTS (SELECT 1 FROM device_sync_members WHERE device_id = ?2)
+            "#,
+        )
+        .bind(source)
+        .bind(target)
+        .execute(&mut *tx)
+        .await?;
+
+        for table in ["episode_actions", "settings", "device_sync_members"] {
+            sqlx::query(&format!(
+                "UPDATE OR IGNORE {} SET device_id = ? WHERE device_id = ?",
+                table
+            ))
+            .bind(target)
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Whatever was not moved is a duplicate and cascades
+        sqlx::query("DELETE FROM devices WHERE id = ?")
+            .bind(source)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE devices SET updated_at = strftime('%s', 'now') WHERE id = ?")
+            .bind(target)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO subscription_events (user_id, device_id, podcast_url, action, timestamp)
+            SELECT DISTINCT ?1, m.device_id, s.podcast_url, 'add', strftime('%s', 'now')
+            FROM device_sync_members m
+            JOIN device_sync_members o ON o.sync_group_id = m.sync_group_id
+            JOIN subscriptions s ON s.device_id = o.device_id AND s.removed_at IS NULL
+            WHERE m.sync_group_id = (SELECT sync_group_id FROM device_sync_members WHERE device_id = ?2)
+              AND NOT EXISTS (
+                SELECT 1 FROM subscriptions a
+                WHERE a.device_id = m.device_id AND a.podcast_url = s.podcast_url
+                  AND a.removed_at IS NULL
+              )
+            "#,
+        )
+        .bind(user_id)
+        .bind(target)
+        .execute(&mut *tx)
+        .await?;
+
+        Self::remove_single_device_groups(&mut tx, user_id).await?;
+        Self::recount_subscribers(&mut tx, &podcast_urls).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn subscribed_podcasts(
+        tx: &mut Transaction<'_, Sqlite>,
+        id: i64,
+    ) -> Result<Vec<String>, Error> {
+        sqlx::query_scalar("SELECT podcast_url FROM subscriptions WHERE device_id = ?")
+            .bind(id)
+            .fetch_all(&mut **tx)
+            .await
+    }
+
+    /// A sync group needs at least two devices
+    async fn remove_single_device_groups(
+        tx: &mut Transaction<'_, Sqlite>,
+        user_id: i64,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            DELETE FROM device_sync_groups
+            WHERE user_id = ? AND (
+                SELECT COUNT(*) FROM device_sync_members m
+                WHERE m.sync_group_id = device_sync_groups.id
+            ) < 2
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Subscriptions removed by cascade are not counted down by the triggers
+    async fn recount_subscribers(
+        tx: &mut Transaction<'_, Sqlite>,
+        podcast_urls: &[String],
+    ) -> Result<(), Error> {
+        for podcast_url in podcast_urls {
+            sqlx::query(
+                r#"
+                UPDATE podcasts
+                SET subscriber_count = (
+                    SELECT COUNT(*) FROM subscriptions
+                    WHERE podcast_url = podcasts.url AND removed_at IS NULL
+                )
+                WHERE url = ?
+                "#,
+            )
+            .bind(podcast_url)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
 }
@@ -16,6 +16,7 @@ pub struct EpisodeActionWithDevice {
     pub device: String,
     pub podcast_url: String,
     pub episode_url: String,
+    pub guid: Option<String>,
     pub action: String,
     pub timestamp: i64,
     pub started: Option<i64>,
@@ -38,7 +39,7 @@ impl EpisodeActionRepository {
             r#"
             SELECT
                 ea.id, ea.user_id, ea.device_id as device_id_fk, d.device_id as device,
-                ea.podcast_url, ea.episode_url, ea.action,
+                ea.podcast_url, ea.episode_url, ea.guid, ea.action,
                 ea.timestamp, ea.started, ea.position, ea.total, ea.created_at
             FROM episode_actions ea
             INNER JOIN devices d ON ea.device_id = d.id
@@ -47,7 +48,7 @@ impl EpisodeActionRepository {
         );
 
         if query.since.is_some() {
-            sql.push_str(" AND ea.timestamp >= ? ");
+            sql.push_str(" AND ea.change_seq > ? ");
         }
 
         if query.podcast.is_some() {
@@ -58,7 +59,30 @@ impl EpisodeActionRepository {
             sql.push_str(" AND d.device_id = ? ");
         }
 
-        sql.push_str(" ORDER BY ea.timestamp DESC");
+        if query.aggregated.unwrap_or(false) {
+            // Only keep the most recent action of each episode
+            sql = format!(
+                r#"
+                SELECT
+                    id, user_id, device_id_fk, device, podcast_url, episode_url, guid, action,
+                    timestamp, started, position, total, created_at
+                FROM (
+                    SELECT
+                        filtered.*,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY episode_url
+                            ORDER BY timestamp DESC, id DESC
+                        ) AS action_rank
+                    FROM ({}) filtered
+                )
+                WHERE action_rank = 1
+                ORDER BY timestamp DESC
+                "#,
+                sql
+            );
+        } else {
+            sql.push_str(" ORDER BY ea.timestamp DESC");
+        }
 
         let mut q = sqlx::query_as::<_, EpisodeActionWithDevice>(&sql);
         q = q.bind(user_id);
@@ -78,6 +102,75 @@ impl EpisodeActionRepository {
         q.fetch_all(&self.pool).await
     }
 
+    /// Most recent actions of a user across all devices
+    pub async fn list_recent(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> Result<Vec<EpisodeActionWithDevice>, sqlx::Error> {
+        sqlx::query_as::<_, EpisodeActionWithDevice>(
+            r#"
+            SELECT
+                ea.id, ea.user_id, ea.device_id as device_id_fk, d.device_id as device,
+                ea.podcast_url, ea.episode_url, ea.guid, ea.action,
+                ea.timestamp, ea.started, ea.position, ea.total, ea.created_at
+            FROM episode_actions ea
+            INNER JOIN devices d ON ea.device_id = d.id
+            WHERE ea.user_id = ?
+            ORDER BY ea.timestamp DESC, ea.id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The most recent action of the user on each of the given episodes
+    pub async fn latest_for_episodes(
+        &self,
+        user_id: i64,
+        episode_urls: &[String],
+    ) -> Result<Vec<EpisodeActionWithDevice>, sqlx::Error> {
+        if episode_urls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = format!(
+            r#"
+            SELECT
+                id, user_id, device_id_fk, device, podcast_url, episode_url, guid, action,
+                timestamp, started, position, total, created_at
+            FROM (
+                SELECT
+                    ea.id, ea.user_id, ea.device_id as device_id_fk, d.device_id as device,
+                    ea.podcast_url, ea.episode_url, ea.guid, ea.action,
+                    ea.timestamp, ea.started, ea.position, ea.total, ea.created_at,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY ea.episode_url
+                        ORDER BY ea.timestamp DESC, ea.id DESC
+                    ) AS action_rank
+                FROM episode_actions ea
+                INNER JOIN devices d ON ea.device_id = d.id
+                WHERE ea.user_id = ? AND ea.episode_url IN ({})
+            )
+            WHERE action_rank = 1
+            "#,
+            vec!["?"; episode_urls.len()].join(",")
+        );
+
+        let mut q = sqlx::query_as::<_, EpisodeActionWithDevice>(&query).bind(user_id);
+        for url in episode_urls {
+            q = q.bind(url);
+        }
+
+        q.fetch_all(&self.pool).await
+    }
+
+    /// Store actions; re-uploading an action with the same natural key
+    /// (user, device, episode, action, timestamp, position) updates it instead
+    /// of adding a duplicate
     pub async fn upload(&self, actions: Vec<EpisodeAction>) -> Result<(), sqlx::Error> {
         let mut tx = self.pool.begin().await?;
 
@@ -85,14 +178,21 @@ impl EpisodeActionRepository {
             sqlx::query(
                 r#"
                 INSERT INTO episode_actions
-                (user_id, device_id, podcast_url, episode_url, action, timestamp, started, position, total)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                (user_id, device_id, podcast_url, episode_url, guid, action, timestamp, started, position, total)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(user_id, device_id, episode_url, action, timestamp, COALESCE(position, -1))
+                DO UPDATE SET
+                    podcast_url = excluded.podcast_url,
+                    guid = COALESCE(excluded.guid, guid),
+                    started = excluded.started,
+                    total = excluded.total
                 "#
             )
             .bind(action.user_id)
             .bind(action.device_id)
             .bind(&action.podcast_url)
             .bind(&action.episode_url)
+            .bind(&action.guid)
             .bind(&action.action)
             .bind(action.timestamp)
             .bind(action.started)
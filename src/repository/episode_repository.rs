@@ -0,0 +1,185 @@
+use crate::models::{Episode, EpisodeMetadata};
+use sqlx::{Row, SqlitePool};
+
+#[derive(Clone)]
+pub struct EpisodeRepository {
+    pool: SqlitePool,
+}
+
+const EPISODE_COLUMNS: &str = r#"
+    e.id, e.podcast_id, p.url AS podcast_url, p.title AS podcast_title,
+    e.guid, e.url, e.title, e.description, e.website, e.duration, e.released
+"#;
+
+/// Builds a `?,?,...` placeholder list for an `IN` clause
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(",")
+}
+
+impl EpisodeRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert or update the episodes of a podcast, identified by their guid.
+    /// Returns the number of stored episodes.
+    pub async fn upsert_for_podcast(
+        &self,
+        podcast_url: &str,
+        episodes: &[EpisodeMetadata<'_>],
+    ) -> Result<usize, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(podcast_id) = sqlx::query("SELECT id FROM podcasts WHERE url = ?")
+            .bind(podcast_url)
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|row| row.get_unchecked::<i64, _>(0))
+        else {
+            return Ok(0);
+        };
+
+        for episode in episodes {
+            sqlx::query(
+                r#"
+                INSERT INTO episodes (podcast_id, guid, url, title, description, website, duration, released)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(podcast_id, guid) DO UPDATE SET
+                    url = excluded.url,
+                    title = excluded.title,
+                    description = excluded.description,
+                    website = excluded.website,
+                    duration = excluded.duration,
+                    released = excluded.released,
+                    updated_at = strftime('%s', 'now')
+                "#,
+            )
+            .bind(podcast_id)
+            .bind(episode.guid)
+            .bind(episode.url)
+            .bind(episode.title)
+            .bind(episode.description)
+            .bind(episode.website)
+            .bind(episode.duration)
+            .bind(episode.released)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(episodes.len())
+    }
+
+    /// Look up catalogued episodes by their media URL
+    pub async fn get_by_urls(&self, urls: &[String]) -> Result<Vec<Episode>, sqlx::Error> {
+        if urls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM episodes e
+            INNER JOIN podcasts p ON e.podcast_id = p.id
+            WHERE e.url IN ({})
+            "#,
+            EPISODE_COLUMNS,
+            placeholders(urls.len())
+        );
+
+        let mut q = sqlx::query_as::<_, Episode>(&query);
+        for url in urls {
+            q = q.bind(url);
+        }
+
+        q.fetch_all(&self.pool).await
+    }
+
+    /// Find a catalogued episode by its media URL, optionally within one podcast
+    pub async fn find(
+        &self,
+        podcast_url: Option<&str>,
+        url: &str,
+    ) -> Result<Option<Episode>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM episodes e
+            INNER JOIN podcasts p ON e.podcast_id = p.id
+            WHERE e.url = ? AND (? IS NULL OR p.url = ?)
+            ORDER BY e.id ASC
+            LIMIT 1
+            "#,
+            EPISODE_COLUMNS
+        );
+
+        sqlx::query_as::<_, Episode>(&query)
+            .bind(url)
+            .bind(podcast_url)
+            .bind(podcast_url)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Episodes matching an FTS5 query, optionally within one podcast
+    pub async fn search(
+        &self,
+        fts_query: &str,
+        podcast_url: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Episode>, sqlx::Error> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM episodes_fts
+            INNER JOIN episodes e ON e.id = episodes_fts.rowid
+            INNER JOIN podcasts p ON e.podcast_id = p.id
+            WHERE episodes_fts MATCH ? AND (? IS NULL OR p.url = ?)
+            ORDER BY bm25(episodes_fts, 5.0, 1.0), e.released DESC
+            LIMIT ?
+            "#,
+            EPISODE_COLUMNS
+        );
+
+        sqlx::query_as::<_, Episode>(&query)
+            .bind(fts_query)
+            .bind(podcast_url)
+            .bind(podcast_url)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Newest episodes of the given podcasts catalogued after the change
+    /// cursor `since`
+    pub async fn list_added_since(
+        &self,
+        podcast_urls: &[String],
+        since: i64,
+        limit: i64,
+    ) -> Result<Vec<Episode>, sqlx::Error> {
+        if podcast_urls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM episodes e
+            INNER JOIN podcasts p ON e.podcast_id = p.id
+            WHERE p.url IN ({}) AND e.change_seq > ?
+            ORDER BY COALESCE(e.released, e.created_at) DESC
+            LIMIT ?
+            "#,
+            EPISODE_COLUMNS,
+            placeholders(podcast_urls.len())
+        );
+
+        let mut q = sqlx::query_as::<_, Episode>(&query);
+        for url in podcast_urls {
+            q = q.bind(url);
+        }
+
+        q.bind(since).bind(limit).fetch_all(&self.pool).await
+    }
+}
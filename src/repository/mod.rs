@@ -1,19 +1,47 @@
+pub mod admin_session_repository;
+pub mod bookmark_repository;
+pub mod change_log_repository;
+pub mod chapter_repository;
 pub mod device_repository;
 pub mod device_sync_repository;
 pub mod episode_action_repository;
+pub mod episode_repository;
 pub mod favorite_repository;
+pub mod podcast_list_repository;
 pub mod podcast_repository;
 pub mod session_repository;
 pub mod setting_repository;
 pub mod subscription_repository;
+pub mod url_repository;
 pub mod user_repository;
 
+pub use admin_session_repository::AdminSessionRepository;
+pub use bookmark_repository::BookmarkRepository;
+pub use change_log_repository::ChangeLogRepository;
+pub use chapter_repository::ChapterRepository;
 pub use device_repository::DeviceRepository;
 pub use device_sync_repository::DeviceSyncRepository;
 pub use episode_action_repository::{EpisodeActionRepository, EpisodeActionWithDevice};
+pub use episode_repository::EpisodeRepository;
 pub use favorite_repository::FavoriteRepository;
+pub use podcast_list_repository::PodcastListRepository;
 pub use podcast_repository::PodcastRepository;
 pub use session_repository::SessionRepository;
 pub use setting_repository::{SettingKey, SettingRepository};
 pub use subscription_repository::SubscriptionRepository;
+pub use url_repository::UrlRepository;
 pub use user_repository::UserRepository;
+
+/// Migrated database in a temporary directory, removed with the returned
+/// `TempDir`
+#[cfg(test)]
+pub async fn test_db() -> (sqlx::SqlitePool, tempfile::TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let db_path = dir.path().join("test.db");
+    std::fs::File::create(&db_path).unwrap();
+    let pool = sqlx::SqlitePool::connect(&format!("sqlite:{}", db_path.display()))
+        .await
+        .unwrap();
+    crate::run_migrations(&pool, &[]).await.unwrap();
+    (pool, dir)
+}
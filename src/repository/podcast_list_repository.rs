@@ -0,0 +1,143 @@
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+use crate::models::PodcastList;
+
+#[derive(Clone)]
+pub struct PodcastListRepository {
+    pool: SqlitePool,
+}
+
+/// Replace the podcasts of a list, keeping the given order
+async fn replace_entries(
+    tx: &mut Transaction<'_, Sqlite>,
+    list_id: i64,
+    podcast_urls: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM podcast_list_entries WHERE list_id = ?")
+        .bind(list_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for (position, url) in podcast_urls.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO podcast_list_entries (list_id, podcast_url, position)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(list_id)
+        .bind(url)
+        .bind(position as i64)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+impl PodcastListRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Returns None if the user already has a list of that name
+    pub async fn create(
+        &self,
+        user_id: i64,
+        name: &str,
+        title: &str,
+        podcast_urls: &[String],
+    ) -> Result<Option<PodcastList>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let list = sqlx::query_as::<_, PodcastList>(
+            r#"
+            INSERT INTO podcast_lists (user_id, name, title)
+            VALUES (?, ?, ?)
+            ON CONFLICT(user_id, name) DO NOTHING
+            RETURNING id, user_id, name, title, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(title)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(list) = list else {
+            return Ok(None);
+        };
+
+        replace_entries(&mut tx, list.id, podcast_urls).await?;
+        tx.commit().await?;
+        Ok(Some(list))
+    }
+
+    pub async fn find(&self, user_id: i64, name: &str) -> Result<Option<PodcastList>, sqlx::Error> {
+        sqlx::query_as::<_, PodcastList>(
+            r#"
+            SELECT id, user_id, name, title, created_at, updated_at
+            FROM podcast_lists
+            WHERE user_id = ? AND name = ?
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn list_by_user(&self, user_id: i64) -> Result<Vec<PodcastList>, sqlx::Error> {
+        sqlx::query_as::<_, PodcastList>(
+            r#"
+            SELECT id, user_id, name, title, created_at, updated_at
+            FROM podcast_lists
+            WHERE user_id = ?
+            ORDER BY title COLLATE NOCASE, id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Podcast URLs of a list in list order
+    pub async fn podcast_urls(&self, list_id: i64) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT podcast_url FROM podcast_list_entries
+            WHERE list_id = ?
+            ORDER BY position
+            "#,
+        )
+        .bind(list_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn set_podcasts(
+        &self,
+        list_id: i64,
+        podcast_urls: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        replace_entries(&mut tx, list_id, podcast_urls).await?;
+        sqlx::query("UPDATE podcast_lists SET updated_at = strftime('%s', 'now') WHERE id = ?")
+            .bind(list_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Entries are removed along with the list
+    pub async fn delete(&self, list_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM podcast_lists WHERE id = ?")
+            .bind(list_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,18 +1,37 @@
 use crate::error::AppResult;
-use crate::models::Podcast;
-use sqlx::{Row, SqlitePool};
+use crate::models::{FeedFetchState, Podcast};
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
 
+#[derive(Clone)]
 pub struct PodcastRepository {
     pool: SqlitePool,
 }
 
+const PODCAST_COLUMNS: &str = r#"
+    p.id, p.url, p.title, p.description, p.website, p.logo_url,
+    p.subscriber_count, p.created_at, p.updated_at
+"#;
+
+fn podcast_from_row(row: &SqliteRow) -> Podcast {
+    Podcast {
+        id: row.get_unchecked(0),
+        url: row.get_unchecked(1),
+        title: row.get_unchecked(2),
+        description: row.get_unchecked(3),
+        website: row.get_unchecked(4),
+        logo_url: row.get_unchecked(5),
+        subscriber_count: row.get_unchecked(6),
+        created_at: row.get_unchecked(7),
+        updated_at: row.get_unchecked(8),
+    }
+}
+
 impl PodcastRepository {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
 
     /// Get podcast by URL
-    #[allow(dead_code)]
     pub async fn get_by_url(&self, url: &str) -> AppResult<Option<Podcast>> {
         let result = sqlx::query(
             r#"
@@ -99,7 +118,6 @@ impl PodcastRepository {
     }
 
     /// Create or update podcast metadata
-    #[allow(dead_code)]
     pub async fn upsert(
         &self,
         url: &str,
@@ -130,4 +148,245 @@ impl PodcastRepository {
 
         Ok(())
     }
+
+    /// List subscribed podcasts whose feed was not fetched since the given time,
+    /// never fetched feeds first
+    pub async fn list_due_for_refresh(
+        &self,
+        fetched_before: i64,
+        limit: i64,
+    ) -> AppResult<Vec<FeedFetchState>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT url, etag, last_modified
+            FROM podcasts
+            WHERE subscriber_count > 0
+              AND (last_fetched_at IS NULL OR last_fetched_at < ?)
+            ORDER BY last_fetched_at IS NOT NULL, last_fetched_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(fetched_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| FeedFetchState {
+                url: row.get_unchecked(0),
+                etag: row.get_unchecked(1),
+                last_modified: row.get_unchecked(2),
+            })
+            .collect())
+    }
+
+    /// Remember that a feed was fetched, together with its cache validators
+    pub async fn record_fetch(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE podcasts
+            SET etag = ?, last_modified = ?, last_fetched_at = strftime('%s', 'now')
+            WHERE url = ?
+            "#,
+        )
+        .bind(etag)
+        .bind(last_modified)
+        .bind(url)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remember a fetch attempt without touching the cache validators
+    pub async fn mark_fetched(&self, url: &str) -> AppResult<()> {
+        sqlx::query("UPDATE podcasts SET last_fetched_at = strftime('%s', 'now') WHERE url = ?")
+            .bind(url)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Podcasts with the most subscribers, using `idx_podcasts_subscriber_count`
+    pub async fn toplist(&self, limit: i64) -> AppResult<Vec<Podcast>> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM podcasts p
+            WHERE p.subscriber_count > 0
+            ORDER BY p.subscriber_count DESC
+            LIMIT ?
+            "#,
+            PODCAST_COLUMNS
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(podcast_from_row).collect())
+    }
+
+    /// Podcasts matching an FTS5 query, best matches first.
+    /// Title matches weigh more than description or URL matches.
+    pub async fn search(&self, fts_query: &str, limit: i64) -> AppResult<Vec<Podcast>> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM podcasts_fts
+            INNER JOIN podcasts p ON p.id = podcasts_fts.rowid
+            WHERE podcasts_fts MATCH ?
+            ORDER BY bm25(podcasts_fts, 10.0, 2.0, 1.0), p.subscriber_count DESC
+            LIMIT ?
+            "#,
+            PODCAST_COLUMNS
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(fts_query)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(podcast_from_row).collect())
+    }
+
+    /// Replace the directory tags of a podcast
+    pub async fn set_tags(&self, url: &str, tags: &[String]) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "DELETE FROM podcast_tags WHERE podcast_id = (SELECT id FROM podcasts WHERE url = ?)",
+        )
+        .bind(url)
+        .execute(&mut *tx)
+        .await?;
+
+        for tag in tags {
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO podcast_tags (podcast_id, tag)
+                SELECT id, ? FROM podcasts WHERE url = ?
+                "#,
+            )
+            .bind(tag)
+            .bind(url)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Most used tags with the number of subscribed podcasts carrying them
+    pub async fn top_tags(&self, limit: i64) -> AppResult<Vec<(String, i64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT t.tag, COUNT(*) AS usage
+            FROM podcast_tags t
+            INNER JOIN podcasts p ON t.podcast_id = p.id
+            WHERE p.subscriber_count > 0
+            GROUP BY t.tag
+            ORDER BY usage DESC, t.tag ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get_unchecked(0), row.get_unchecked(1)))
+            .collect())
+    }
+
+    /// Most subscribed podcasts carrying a tag
+    pub async fn list_by_tag(&self, tag: &str, limit: i64) -> AppResult<Vec<Podcast>> {
+        let query = format!(
+            r#"
+            SELECT {}
+            FROM podcasts p
+            INNER JOIN podcast_tags t ON t.podcast_id = p.id
+            WHERE t.tag = ? AND p.subscriber_count > 0
+            ORDER BY p.subscriber_count DESC
+            LIMIT ?
+            "#,
+            PODCAST_COLUMNS
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(tag)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(podcast_from_row).collect())
+    }
+
+    /// Rebuild the co-subscription counts from the active subscriptions
+    pub async fn recompute_similarity(&self) -> AppResult<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM podcast_similarity")
+            .execute(&mut *tx)
+            .await?;
+
+        let result = sqlx::query(
+            r#"
+            WITH user_podcasts AS (
+                SELECT DISTINCT user_id, podcast_url
+                FROM subscriptions
+                WHERE removed_at IS NULL
+            )
+            INSERT INTO podcast_similarity (podcast_url, similar_url, score)
+            SELECT a.podcast_url, b.podcast_url, COUNT(*)
+            FROM user_podcasts a
+            INNER JOIN user_podcasts b
+                ON a.user_id = b.user_id AND a.podcast_url <> b.podcast_url
+            GROUP BY a.podcast_url, b.podcast_url
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Podcasts most often co-subscribed with the user's current subscriptions,
+    /// excluding the ones the user already has
+    pub async fn suggestions_for_user(&self, user_id: i64, limit: i64) -> AppResult<Vec<Podcast>> {
+        let query = format!(
+            r#"
+            WITH mine AS (
+                SELECT DISTINCT podcast_url
+                FROM subscriptions
+                WHERE user_id = ?1 AND removed_at IS NULL
+            )
+            SELECT {}
+            FROM podcast_similarity s
+            INNER JOIN podcasts p ON p.url = s.similar_url
+            WHERE s.podcast_url IN (SELECT podcast_url FROM mine)
+              AND s.similar_url NOT IN (SELECT podcast_url FROM mine)
+            GROUP BY p.id
+            ORDER BY SUM(s.score) DESC, p.subscriber_count DESC
+            LIMIT ?2
+            "#,
+            PODCAST_COLUMNS
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(user_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.iter().map(podcast_from_row).collect())
+    }
 }
@@ -47,6 +47,15 @@ impl SessionRepository {
         Ok(())
     }
 
+    /// Delete all sessions of a user, logging them out everywhere
+    pub async fn delete_by_user(&self, user_id: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM sessions WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
     #[allow(dead_code)]
     pub async fn delete_expired(&self, current_time: i64) -> Result<u64, sqlx::Error> {
         let result = sqlx::query("DELETE FROM sessions WHERE expires_at < ?")
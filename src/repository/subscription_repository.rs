@@ -1,3 +1,4 @@
+use crate::models::{RejectedSubscriptionChange, SubscriptionEvent};
 use sqlx::{Error, Row, SqlitePool};
 
 #[derive(Clone)]
@@ -5,32 +6,48 @@ pub struct SubscriptionRepository {
     pool: SqlitePool,
 }
 
+/// Builds a `?,?,...` placeholder list for an `IN` clause
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(",")
+}
+
 impl SubscriptionRepository {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
 
-    pub async fn list_by_device(
+    /// List the active subscriptions of a set of devices (e.g. a sync group),
+    /// returning each podcast URL only once
+    pub async fn list_by_devices(
         &self,
         user_id: i64,
-        device_id: i64,
+        device_ids: &[i64],
     ) -> Result<Vec<String>, sqlx::Error> {
-        let rows = sqlx::query(
+        if device_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = format!(
             r#"
             SELECT podcast_url
             FROM subscriptions
-            WHERE user_id = ? AND device_id = ? AND removed_at IS NULL
-            ORDER BY added_at ASC
+            WHERE user_id = ? AND device_id IN ({}) AND removed_at IS NULL
+            GROUP BY podcast_url
+            ORDER BY MIN(added_at) ASC
             "#,
-        )
-        .bind(user_id)
-        .bind(device_id)
-        .fetch_all(&self.pool)
-        .await?;
+            placeholders(device_ids.len())
+        );
+
+        let mut q = sqlx::query(&query).bind(user_id);
+        for device_id in device_ids {
+            q = q.bind(device_id);
+        }
+
+        let rows = q.fetch_all(&self.pool).await?;
 
         Ok(rows
             .into_iter()
-            .map(|row| row.get_unchecked::<i64, _>(0).to_string())
+            .map(|row| row.get_unchecked::<String, _>(0))
             .collect())
     }
 
@@ -49,160 +66,221 @@ impl SubscriptionRepository {
 
         Ok(rows
             .into_iter()
-            .map(|row| row.get_unchecked::<i64, _>(0).to_string())
+            .map(|row| row.get_unchecked::<String, _>(0))
             .collect())
     }
 
+    /// Get subscription changes for a set of devices after the given change
+    /// cursor (see `ChangeLogRepository::current_cursor`).
+    ///
+    /// A podcast only counts as removed if no device of the set is still
+    /// subscribed to it.
     pub async fn get_changes_since(
         &self,
         user_id: i64,
-        device_id: i64,
+        device_ids: &[i64],
         since: i64,
     ) -> Result<(Vec<String>, Vec<String>), sqlx::Error> {
-        let added = sqlx::query(
+        if device_ids.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let devices = placeholders(device_ids.len());
+
+        let added_query = format!(
             r#"
             SELECT podcast_url
             FROM subscriptions
-            WHERE user_id = ? AND device_id = ? AND added_at > ? AND removed_at IS NULL
-            ORDER BY added_at ASC
+            WHERE user_id = ? AND device_id IN ({}) AND change_seq > ? AND removed_at IS NULL
+            GROUP BY podcast_url
+            ORDER BY MIN(change_seq) ASC
             "#,
-        )
-        .bind(user_id)
-        .bind(device_id)
-        .bind(since)
-        .fetch_all(&self.pool)
-        .await?;
+            devices
+        );
+
+        let mut q = sqlx::query(&added_query).bind(user_id);
+        for device_id in device_ids {
+            q = q.bind(device_id);
+        }
+        let added = q.bind(since).fetch_all(&self.pool).await?;
 
-        let removed = sqlx::query(
+        let removed_query = format!(
             r#"
-            SELECT podcast_url
-            FROM subscriptions
-            WHERE user_id = ? AND device_id = ? AND removed_at > ? AND removed_at IS NOT NULL
-            ORDER BY removed_at ASC
+            SELECT s.podcast_url
+            FROM subscriptions s
+            WHERE s.user_id = ? AND s.device_id IN ({devices})
+              AND s.removed_at IS NOT NULL AND s.change_seq > ?
+              AND NOT EXISTS (
+                SELECT 1 FROM subscriptions a
+                WHERE a.user_id = s.user_id AND a.device_id IN ({devices})
+                  AND a.podcast_url = s.podcast_url AND a.removed_at IS NULL
+              )
+            GROUP BY s.podcast_url
+            ORDER BY MAX(s.change_seq) ASC
             "#,
-        )
-        .bind(user_id)
-        .bind(device_id)
-        .bind(since)
-        .fetch_all(&self.pool)
-        .await?;
+        );
+
+        let mut q = sqlx::query(&removed_query).bind(user_id);
+        for device_id in device_ids {
+            q = q.bind(device_id);
+        }
+        q = q.bind(since);
+        for device_id in device_ids {
+            q = q.bind(device_id);
+        }
+        let removed = q.fetch_all(&self.pool).await?;
 
         let added_urls = added
             .into_iter()
-            .map(|row| row.get_unchecked::<i64, _>(0).to_string())
+            .map(|row| row.get_unchecked::<String, _>(0))
             .collect();
 
         let removed_urls = removed
             .into_iter()
-            .map(|row| row.get_unchecked::<i64, _>(0).to_string())
+            .map(|row| row.get_unchecked::<String, _>(0))
             .collect();
 
         Ok((added_urls, removed_urls))
     }
 
+    /// Replace the subscriptions of a set of devices with the given URLs.
+    /// The current state is the union over all devices of the set.
     pub async fn set_subscriptions(
         &self,
         user_id: i64,
-        device_id: i64,
+        device_ids: &[i64],
         podcast_urls: Vec<String>,
-    ) -> Result<(), sqlx::Error> {
+    ) -> Result<Vec<RejectedSubscriptionChange>, sqlx::Error> {
+        let current_urls: std::collections::HashSet<String> = self
+            .list_by_devices(user_id, device_ids)
+            .await?
+            .into_iter()
+            .collect();
+
+        let new_urls: std::collections::HashSet<String> = podcast_urls.into_iter().collect();
+
+        let changes = crate::models::SubscriptionChanges {
+            add: new_urls.difference(&current_urls).cloned().collect(),
+            remove: current_urls.difference(&new_urls).cloned().collect(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+
+        self.apply_changes(user_id, device_ids, changes).await
+    }
+
+    /// Apply subscription changes to every device of the set.
+    ///
+    /// Changes are recorded in `subscription_events`, from which triggers
+    /// derive `subscriptions`. Only changes of the current state are recorded:
+    /// adding an active or removing an inactive subscription is a no-op.
+    ///
+    /// Conflicts are resolved by the time the changes were made, the last
+    /// writer wins: a change older than the newest opposite change of the same
+    /// podcast on any device of the set is rejected and returned.
+    pub async fn apply_changes(
+        &self,
+        user_id: i64,
+        device_ids: &[i64],
+        changes: crate::models::SubscriptionChanges,
+    ) -> Result<Vec<RejectedSubscriptionChange>, sqlx::Error> {
         let mut tx = self.pool.begin().await?;
+        let mut rejected = Vec::new();
 
-        let current = sqlx::query(
+        let latest_query = format!(
             r#"
-            SELECT podcast_url
-            FROM subscriptions
-            WHERE user_id = ? AND device_id = ? AND removed_at IS NULL
+            SELECT action, timestamp
+            FROM subscription_events
+            WHERE user_id = ? AND device_id IN ({}) AND podcast_url = ?
+            ORDER BY timestamp DESC, id DESC
+            LIMIT 1
             "#,
-        )
-        .bind(user_id)
-        .bind(device_id)
-        .fetch_all(&mut *tx)
-        .await?;
+            placeholders(device_ids.len())
+        );
 
-        let current_urls: std::collections::HashSet<String> = current
-            .into_iter()
-            .map(|row| row.get_unchecked::<i64, _>(0).to_string())
-            .collect();
+        let actions = [("add", &changes.add), ("remove", &changes.remove)];
+        for (action, podcast_urls) in actions {
+            for podcast_url in podcast_urls {
+                let mut q = sqlx::query(&latest_query).bind(user_id);
+                for device_id in device_ids {
+                    q = q.bind(device_id);
+                }
+                let latest = q.bind(podcast_url).fetch_optional(&mut *tx).await?;
 
-        let new_urls: std::collections::HashSet<String> = podcast_urls.into_iter().collect();
-
-        for url in &new_urls {
-            if !current_urls.contains(url) {
-                sqlx::query(
-                    r#"
-                    INSERT OR IGNORE INTO subscriptions (user_id, device_id, podcast_url)
-                    VALUES (?, ?, ?)
-                    "#,
-                )
-                .bind(user_id)
-                .bind(device_id)
-                .bind(url)
-                .execute(&mut *tx)
-                .await?;
-            }
-        }
+                if let Some(latest) = latest {
+                    let latest_action = latest.get_unchecked::<String, _>(0);
+                    let latest_timestamp = latest.get_unchecked::<i64, _>(1);
+                    if latest_action != action && latest_timestamp > changes.timestamp {
+                        rejected.push(RejectedSubscriptionChange {
+                            podcast: podcast_url.clone(),
+                            action: action.to_string(),
+                            superseded_at: latest_timestamp,
+                        });
+                        continue;
+                    }
+                }
 
-        for url in &current_urls {
-            if !new_urls.contains(url) {
-                sqlx::query(
-                    r#"
-                    UPDATE subscriptions
-                    SET removed_at = strftime('%s', 'now')
-                    WHERE user_id = ? AND device_id = ? AND podcast_url = ? AND removed_at IS NULL
-                    "#,
-                )
-                .bind(user_id)
-                .bind(device_id)
-                .bind(url)
-                .execute(&mut *tx)
-                .await?;
+                for device_id in device_ids {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO subscription_events (user_id, device_id, podcast_url, action, timestamp)
+                        SELECT ?1, ?2, ?3, ?4, ?5
+                        -- adds need an inactive, removes an active subscription
+                        WHERE EXISTS (
+                            SELECT 1 FROM subscriptions
+                            WHERE user_id = ?1 AND device_id = ?2 AND podcast_url = ?3
+                              AND removed_at IS NULL
+                        ) = (?4 = 'remove')
+                        "#,
+                    )
+                    .bind(user_id)
+                    .bind(device_id)
+                    .bind(podcast_url)
+                    .bind(action)
+                    .bind(changes.timestamp)
+                    .execute(&mut *tx)
+                    .await?;
+                }
             }
         }
 
         tx.commit().await?;
-        Ok(())
+        Ok(rejected)
     }
 
-    pub async fn apply_changes(
+    /// Subscription history of a device, oldest first
+    pub async fn history(
         &self,
         user_id: i64,
         device_id: i64,
-        changes: crate::models::SubscriptionChanges,
-    ) -> Result<(), sqlx::Error> {
-        let mut tx = self.pool.begin().await?;
+    ) -> Result<Vec<SubscriptionEvent>, Error> {
+        sqlx::query_as::<_, SubscriptionEvent>(
+            r#"
+            SELECT podcast_url, action, timestamp
+            FROM subscription_events
+            WHERE user_id = ? AND device_id = ?
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(device_id)
+        .fetch_all(&self.pool)
+        .await
+    }
 
-        for podcast_url in changes.add {
-            sqlx::query(
-                r#"
-                INSERT OR IGNORE INTO subscriptions (user_id, device_id, podcast_url)
-                VALUES (?, ?, ?)
-                "#,
-            )
-            .bind(user_id)
-            .bind(device_id)
-            .bind(podcast_url)
-            .execute(&mut *tx)
-            .await?;
-        }
+    /// Give every device of the set the union of their active subscriptions.
+    /// Used when devices are joined into a sync group.
+    pub async fn merge_devices(&self, user_id: i64, device_ids: &[i64]) -> Result<(), Error> {
+        let union = self.list_by_devices(user_id, device_ids).await?;
 
-        for podcast_url in changes.remove {
-            sqlx::query(
-                r#"
-                UPDATE subscriptions
-                SET removed_at = strftime('%s', 'now')
-                WHERE user_id = ? AND device_id = ? AND podcast_url = ? AND removed_at IS NULL
-                "#,
-            )
-            .bind(user_id)
-            .bind(device_id)
-            .bind(podcast_url)
-            .execute(&mut *tx)
-            .await?;
-        }
+        let changes = crate::models::SubscriptionChanges {
+            add: union,
+            remove: Vec::new(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
 
-        tx.commit().await?;
-        Ok(())
+        self.apply_changes(user_id, device_ids, changes)
+            .await
+            .map(|_| ())
     }
 
     pub async fn count(&self, user_id: i64, device_id: Option<i64>) -> Result<i64, Error> {
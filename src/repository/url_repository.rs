@@ -0,0 +1,288 @@
+use sqlx::{Row, SqlitePool};
+
+use crate::models::PodcastAlias;
+
+/// Rewrites podcast and episode URLs everywhere they are stored, merging rows
+/// that become duplicates, and keeps the aliases of moved podcasts
+#[derive(Clone)]
+pub struct UrlRepository {
+    pool: SqlitePool,
+}
+
+impl UrlRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Every distinct podcast URL stored in any table
+    pub async fn podcast_urls(&self) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT podcast_url FROM subscriptions WHERE removed_at IS NULL
+            UNION SELECT url FROM podcasts
+            UNION SELECT podcast_url FROM episode_actions
+            UNION SELECT podcast_url FROM favorite_episodes
+            UNION SELECT podcast_url FROM podcast_list_entries
+            UNION SELECT podcast_url FROM chapters
+            UNION SELECT podcast_url FROM bookmarks
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get_unchecked::<String, _>(0))
+            .collect())
+    }
+
+    /// Every distinct episode URL stored in any table
+    pub async fn episode_urls(&self) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT episode_url FROM episode_actions
+            UNION SELECT episode_url FROM favorite_episodes
+            UNION SELECT episode_url FROM chapters
+            UNION SELECT episode_url FROM bookmarks
+            UNION SELECT episode_url FROM settings WHERE episode_url IS NOT NULL
+            UNION SELECT url FROM episodes
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get_unchecked::<String, _>(0))
+            .collect())
+    }
+
+    /// Replace podcast URL `old` with `new`. Active subscriptions to `old`
+    /// are moved by recording a removal of `old` and an addition of `new`, so
+    /// that syncing clients learn about the move; the history under `old`
+    /// stays as it was. Settings and catalogued podcasts that exist under
+    /// both URLs keep the copy under `new`.
+    pub async fn rewrite_podcast_url(&self, old: &str, new: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Episodes and tags of the dropped copy cascade; it is refetched anyway.
+        // Renamed before the subscriptions move, whose triggers would create
+        // an empty entry for `new`.
+        sqlx::query("UPDATE OR IGNORE podcasts SET url = ? WHERE url = ?")
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM podcasts WHERE url = ?")
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO subscription_events (user_id, device_id, podcast_url, action, timestamp)
+            SELECT user_id, device_id, ?1, 'add', strftime('%s', 'now')
+            FROM subscriptions s
+            WHERE podcast_url = ?2 AND removed_at IS NULL
+              AND NOT EXISTS (
+                SELECT 1 FROM subscriptions n
+                WHERE n.user_id = s.user_id AND n.device_id = s.device_id
+                  AND n.podcast_url = ?1 AND n.removed_at IS NULL
+              )
+            "#,
+        )
+        .bind(new)
+        .bind(old)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO subscription_events (user_id, device_id, podcast_url, action, timestamp)
+            SELECT user_id, device_id, podcast_url, 'remove', strftime('%s', 'now')
+            FROM subscriptions
+            WHERE podcast_url = ? AND removed_at IS NULL
+            "#,
+        )
+        .bind(old)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE episode_actions SET podcast_url = ? WHERE podcast_url = ?")
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE favorite_episodes SET podcast_url = ? WHERE podcast_url = ?")
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE chapters SET podcast_url = ? WHERE podcast_url = ?")
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE bookmarks SET podcast_url = ? WHERE podcast_url = ?")
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        for table in ["podcast_list_entries", "settings"] {
+            sqlx::query(&format!(
+                "UPDATE OR IGNORE {} SET podcast_url = ? WHERE podcast_url = ?",
+                table
+            ))
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(&format!("DELETE FROM {} WHERE podcast_url = ?", table))
+                .bind(old)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE podcasts
+            SET subscriber_count = (
+                SELECT COUNT(*) FROM subscriptions
+                WHERE podcast_url = podcasts.url AND removed_at IS NULL
+            )
+            WHERE url = ?
+            "#,
+        )
+        .bind(new)
+        .execute(&mut *tx)
+        .await?;
+
+        // Rebuilt with the next suggestion refresh
+        sqlx::query("DELETE FROM podcast_similarity WHERE podcast_url = ?1 OR similar_url = ?1")
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Replace episode URL `old` with `new`, dropping actions, favorites,
+    /// chapters and settings that already exist under `new`
+    pub async fn rewrite_episode_url(&self, old: &str, new: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for table in [
+            "episode_actions",
+            "favorite_episodes",
+            "chapters",
+            "settings",
+        ] {
+            sqlx::query(&format!(
+                "UPDATE OR IGNORE {} SET episode_url = ? WHERE episode_url = ?",
+                table
+            ))
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(&format!("DELETE FROM {} WHERE episode_url = ?", table))
+                .bind(old)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("UPDATE bookmarks SET episode_url = ? WHERE episode_url = ?")
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE episodes SET url = ? WHERE url = ?")
+            .bind(new)
+            .bind(old)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// URL the podcast known as `url` is stored under, if `url` is an alias
+    pub async fn resolve_alias(&self, url: &str) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT podcast_url FROM podcast_aliases WHERE alias_url = ?")
+            .bind(url)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn aliases(&self) -> Result<Vec<PodcastAlias>, sqlx::Error> {
+        sqlx::query_as::<_, PodcastAlias>(
+            r#"
+            SELECT alias_url, podcast_url, source, created_at
+            FROM podcast_aliases
+            ORDER BY podcast_url, alias_url
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Make `alias` an alias of `podcast_url`. Aliases of `alias` follow it
+    /// to `podcast_url`, and `podcast_url` stops being an alias itself, so
+    /// that a feed moving back to an old URL does not create a cycle.
+    pub async fn add_alias(
+        &self,
+        alias: &str,
+        podcast_url: &str,
+        source: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE podcast_aliases SET podcast_url = ? WHERE podcast_url = ?")
+            .bind(podcast_url)
+            .bind(alias)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM podcast_aliases WHERE alias_url = ?")
+            .bind(podcast_url)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO podcast_aliases (alias_url, podcast_url, source)
+            VALUES (?, ?, ?)
+            ON CONFLICT(alias_url) DO UPDATE SET
+                podcast_url = excluded.podcast_url,
+                source = excluded.source,
+                created_at = strftime('%s', 'now')
+            "#,
+        )
+        .bind(alias)
+        .bind(podcast_url)
+        .bind(source)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Returns false if `alias` was not an alias
+    pub async fn delete_alias(&self, alias: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM podcast_aliases WHERE alias_url = ?")
+            .bind(alias)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -21,7 +21,7 @@ impl UserRepository {
             r#"
             INSERT INTO users (username, password_hash, is_admin)
             VALUES (?, ?, ?)
-            RETURNING id, username, password_hash, is_admin, created_at
+            RETURNING id, username, password_hash, is_admin, disabled, created_at
             "#,
         )
         .bind(username)
@@ -35,7 +35,8 @@ impl UserRepository {
             username: result.get_unchecked::<&str, _>(1).to_string(),
             password_hash: result.get_unchecked::<&str, _>(2).to_string(),
             is_admin: result.get_unchecked::<i32, _>(3) != 0,
-            created_at: result.get_unchecked(4),
+            disabled: result.get_unchecked::<i32, _>(4) != 0,
+            created_at: result.get_unchecked(5),
         })
     }
 
@@ -45,6 +46,7 @@ impl UserRepository {
             SELECT
                 id, username, password_hash,
                 CAST(is_admin AS INTEGER) as is_admin,
+                CAST(disabled AS INTEGER) as disabled,
                 created_at
             FROM users
             WHERE id = ?
@@ -59,7 +61,8 @@ impl UserRepository {
             username: row.get_unchecked::<&str, _>(1).to_string(),
             password_hash: row.get_unchecked::<&str, _>(2).to_string(),
             is_admin: row.get_unchecked::<i32, _>(3) != 0,
-            created_at: row.get_unchecked(4),
+            disabled: row.get_unchecked::<i32, _>(4) != 0,
+            created_at: row.get_unchecked(5),
         }))
     }
 
@@ -69,6 +72,7 @@ impl UserRepository {
             SELECT
                 id, username, password_hash,
                 CAST(is_admin AS INTEGER) as is_admin,
+                CAST(disabled AS INTEGER) as disabled,
                 created_at
             FROM users
             WHERE username = ?
@@ -83,7 +87,8 @@ impl UserRepository {
             username: row.get_unchecked::<&str, _>(1).to_string(),
             password_hash: row.get_unchecked::<&str, _>(2).to_string(),
             is_admin: row.get_unchecked::<i32, _>(3) != 0,
-            created_at: row.get_unchecked(4),
+            disabled: row.get_unchecked::<i32, _>(4) != 0,
+            created_at: row.get_unchecked(5),
         }))
     }
 
@@ -93,4 +98,60 @@ impl UserRepository {
             .await?;
         Ok(result.get_unchecked::<i64, _>(0) == 0)
     }
+
+    pub async fn list_all(&self) -> Result<Vec<User>, Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                id, username, password_hash,
+                CAST(is_admin AS INTEGER) as is_admin,
+                CAST(disabled AS INTEGER) as disabled,
+                created_at
+            FROM users
+            ORDER BY username ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| User {
+                id: row.get_unchecked(0),
+                username: row.get_unchecked::<&str, _>(1).to_string(),
+                password_hash: row.get_unchecked::<&str, _>(2).to_string(),
+                is_admin: row.get_unchecked::<i32, _>(3) != 0,
+                disabled: row.get_unchecked::<i32, _>(4) != 0,
+                created_at: row.get_unchecked(5),
+            })
+            .collect())
+    }
+
+    pub async fn update_password(&self, id: i64, password_hash: &str) -> Result<bool, Error> {
+        let result = sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+            .bind(password_hash)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn set_disabled(&self, id: i64, disabled: bool) -> Result<bool, Error> {
+        let result = sqlx::query("UPDATE users SET disabled = ? WHERE id = ?")
+            .bind(disabled)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a user; devices, subscriptions, episode actions, settings,
+    /// sessions and favorites are removed through `ON DELETE CASCADE`
+    pub async fn delete(&self, id: i64) -> Result<bool, Error> {
+        let result = sqlx::query("DELETE FROM users WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
@@ -1,17 +1,38 @@
-use warp::Filter;
+use serde::de::DeserializeOwned;
+use warp::{Filter, Rejection};
 
 use crate::config::Config;
+use crate::handlers::format::FormatQueryParams;
 use crate::handlers::{
-    auth, clientconfig, device_sync, devices, episodes, favorites, settings, subscriptions,
+    admin, aliases, auth, bookmarks, chapters, clientconfig, device_sync, devices, directory,
+    episodes, favorites, gpoddersync, podcast_lists, settings, subscriptions, suggestions, users,
 };
-use crate::middleware::{with_auth, AuthService};
+use crate::middleware::{with_admin_session, with_auth, AuthService};
 use crate::state::AppState;
 
+/// URL-encoded form body as posted by the admin web UI
+fn form_body<T: DeserializeOwned + Send>() -> impl Filter<Extract = (T,), Error = Rejection> + Clone
+{
+    warp::body::content_length_limit(16 * 1024).and(warp::body::form())
+}
+
+/// Path segment of the form `{name}.{format}`, extracting the format
+fn with_format(name: &'static str) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::path::param::<String>().and_then(move |segment: String| async move {
+        segment
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .map(str::to_string)
+            .ok_or_else(warp::reject::not_found)
+    })
+}
+
 pub fn create_routes(
     auth_service: AuthService,
     state: AppState,
     config: Config,
 ) -> impl Filter<Extract = impl warp::Reply, Error = std::convert::Infallible> + Clone {
+    let admin_filter = with_admin_session(state.admin_session_service.clone());
     let state_filter = warp::any().map(move || state.clone());
 
     let auth_filter = with_auth(auth_service.clone());
@@ -45,6 +66,20 @@ pub fn create_routes(
         .and(warp::body::json())
         .and_then(auth::logout);
 
+    let change_password = warp::post()
+        .and(warp::path!("api" / "2" / "auth" / String / "password.json"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(auth::change_password);
+
+    let delete_account = warp::post()
+        .and(warp::path!("api" / "2" / "auth" / String / "delete.json"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(auth::delete_account);
+
     let list_devices = warp::get()
         .and(warp::path!("api" / "2" / "devices" / String))
         .and(warp::path::end())
@@ -68,6 +103,26 @@ pub fn create_routes(
             },
         );
 
+    let delete_device = warp::delete()
+        .and(warp::path!("api" / "2" / "devices" / String / String))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(
+            |username: String, device_id_with_ext: String, auth, state| async move {
+                let device_id = device_id_with_ext.trim_end_matches(".json");
+                devices::delete_device(username, device_id.to_string(), auth, state).await
+            },
+        );
+
+    let merge_device = warp::post()
+        .and(warp::path!(
+            "api" / "2" / "devices" / String / String / "merge.json"
+        ))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(devices::merge_device);
+
     let get_device_updates = warp::get()
         .and(warp::path!(
             "api" / "2" / "updates" / String / String / ".json"
@@ -113,10 +168,22 @@ pub fn create_routes(
         .and(warp::body::json())
         .and_then(episodes::upload_episode_actions);
 
+    let get_chapters = warp::get()
+        .and(warp::path!("api" / "2" / "chapters" / String))
+        .and(auth_filter.clone())
+        .and(warp::query::<chapters::ChapterQueryParams>())
+        .and(state_filter.clone())
+        .and_then(chapters::get_chapters);
+
+    let upload_chapters = warp::post()
+        .and(warp::path!("api" / "2" / "chapters" / String))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(chapters::upload_chapters);
+
     let get_settings = warp::get()
-        .and(warp::path!(
-            "api" / "2" / "settings" / String / String / ".json"
-        ))
+        .and(warp::path!("api" / "2" / "settings" / String / String))
         .and(warp::query::<settings::SettingsQueryParams>())
         .and(auth_filter.clone())
         .and(state_filter.clone())
@@ -125,9 +192,7 @@ pub fn create_routes(
         });
 
     let save_settings = warp::post()
-        .and(warp::path!(
-            "api" / "2" / "settings" / String / String / ".json"
-        ))
+        .and(warp::path!("api" / "2" / "settings" / String / String))
         .and(warp::query::<settings::SettingsQueryParams>())
         .and(auth_filter.clone())
         .and(state_filter.clone())
@@ -144,6 +209,33 @@ pub fn create_routes(
         .and(warp::any().map(move || config_clone.clone()))
         .and_then(favorites::get_favorites);
 
+    let get_bookmarks = warp::get()
+        .and(warp::path!("api" / "2" / "bookmarks" / String))
+        .and(auth_filter.clone())
+        .and(warp::query::<bookmarks::BookmarkQueryParams>())
+        .and(state_filter.clone())
+        .and_then(bookmarks::get_bookmarks);
+
+    let create_bookmark = warp::post()
+        .and(warp::path!("api" / "2" / "bookmarks" / String))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(bookmarks::create_bookmark);
+
+    let update_bookmark = warp::put()
+        .and(warp::path!("api" / "2" / "bookmarks" / String / String))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(bookmarks::update_bookmark);
+
+    let delete_bookmark = warp::delete()
+        .and(warp::path!("api" / "2" / "bookmarks" / String / String))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(bookmarks::delete_bookmark);
+
     let get_sync_devices = warp::get()
         .and(warp::path!("api" / "2" / "sync-devices" / String / ".json"))
         .and(auth_filter.clone())
@@ -157,14 +249,24 @@ pub fn create_routes(
         .and(warp::body::json())
         .and_then(device_sync::update_sync_groups);
 
+    let get_subscription_history = warp::get()
+        .and(warp::path!(
+            "api" / "2" / "subscriptions" / String / String / "history.json"
+        ))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(subscriptions::get_subscription_history);
+
     let get_subscriptions_simple = warp::get()
         .and(warp::path!("subscriptions" / String / String / String))
+        .and(warp::query::<FormatQueryParams>())
         .and(auth_filter.clone())
         .and(state_filter.clone())
         .and_then(subscriptions::get_subscriptions_simple);
 
     let get_all_subscriptions_simple = warp::get()
         .and(warp::path!("subscriptions" / String / String))
+        .and(warp::query::<FormatQueryParams>())
         .and(auth_filter.clone())
         .and(state_filter.clone())
         .and_then(subscriptions::get_all_subscriptions_simple);
@@ -176,21 +278,353 @@ pub fn create_routes(
         .and(warp::body::bytes())
         .and_then(subscriptions::upload_subscriptions_simple);
 
-    client_config
-        .or(login)
-        .or(logout)
-        .or(list_devices)
+    // Nextcloud gPodder Sync app, reachable with and without `index.php`
+    let gpoddersync_prefix = warp::path("index.php")
+        .or(warp::any())
+        .unify()
+        .and(warp::path("apps"))
+        .and(warp::path("gpoddersync"));
+
+    let gpoddersync_get_subscriptions = warp::get()
+        .and(gpoddersync_prefix)
+        .and(warp::path!("subscriptions"))
+        .and(warp::query::<gpoddersync::SinceQueryParams>())
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(gpoddersync::get_subscriptions);
+
+    let gpoddersync_upload_subscriptions = warp::post()
+        .and(gpoddersync_prefix)
+        .and(warp::path!("subscription_change" / "create"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(gpoddersync::upload_subscription_changes);
+
+    let gpoddersync_get_episode_actions = warp::get()
+        .and(gpoddersync_prefix)
+        .and(warp::path!("episode_action"))
+        .and(warp::query::<gpoddersync::SinceQueryParams>())
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(gpoddersync::get_episode_actions);
+
+    let gpoddersync_upload_episode_actions = warp::post()
+        .and(gpoddersync_prefix)
+        .and(warp::path!("episode_action" / "create"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(gpoddersync::upload_episode_actions);
+
+    let gpoddersync_api = gpoddersync_get_subscriptions
+        .or(gpoddersync_upload_subscriptions)
+        .or(gpoddersync_get_episode_actions)
+        .or(gpoddersync_upload_episode_actions);
+
+    let admin_login_page = warp::get()
+        .and(warp::path!("admin" / "login"))
+        .and(admin_filter.clone())
+        .and_then(admin::login_page);
+
+    let admin_login = warp::post()
+        .and(warp::path!("admin" / "login"))
+        .and(state_filter.clone())
+        .and(form_body())
+        .and_then(admin::login);
+
+    let admin_logout = warp::post()
+        .and(warp::path!("admin" / "logout"))
+        .and(admin_filter.clone())
+        .and(state_filter.clone())
+        .and_then(admin::logout);
+
+    let admin_dashboard = warp::get()
+        .and(warp::path!("admin"))
+        .and(admin_filter.clone())
+        .and(state_filter.clone())
+        .and_then(admin::dashboard);
+
+    let admin_create_user = warp::post()
+        .and(warp::path!("admin" / "users"))
+        .and(admin_filter.clone())
+        .and(state_filter.clone())
+        .and(form_body())
+        .and_then(admin::create_user);
+
+    let admin_user_detail = warp::get()
+        .and(warp::path!("admin" / "users" / i64))
+        .and(admin_filter.clone())
+        .and(state_filter.clone())
+        .and_then(admin::user_detail);
+
+    let admin_reset_password = warp::post()
+        .and(warp::path!("admin" / "users" / i64 / "password"))
+        .and(admin_filter.clone())
+        .and(state_filter.clone())
+        .and(form_body())
+        .and_then(admin::reset_password);
+
+    let admin_delete_user = warp::post()
+        .and(warp::path!("admin" / "users" / i64 / "delete"))
+        .and(admin_filter.clone())
+        .and(state_filter.clone())
+        .and_then(admin::delete_user);
+
+    let admin_static = warp::get()
+        .and(warp::path!("admin" / "static" / String))
+        .and_then(admin::static_asset);
+
+    let admin_ui = admin_login_page
+        .or(admin_login)
+        .or(admin_logout)
+        .or(admin_dashboard)
+        .or(admin_create_user)
+        .or(admin_user_detail)
+        .or(admin_reset_password)
+        .or(admin_delete_user)
+        .or(admin_static);
+
+    let list_users = warp::get()
+        .and(warp::path!("api" / "admin" / "users"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(users::list_users);
+
+    let create_user = warp::post()
+        .and(warp::path!("api" / "admin" / "users"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(users::create_user);
+
+    let get_user = warp::get()
+        .and(warp::path!("api" / "admin" / "users" / String))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(users::get_user);
+
+    let delete_user = warp::delete()
+        .and(warp::path!("api" / "admin" / "users" / String))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(users::delete_user);
+
+    let disable_user = warp::post()
+        .and(warp::path!("api" / "admin" / "users" / String / "disable"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(|username, auth, state| async move {
+            users::set_user_disabled(username, true, auth, state).await
+        });
+
+    let enable_user = warp::post()
+        .and(warp::path!("api" / "admin" / "users" / String / "enable"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(|username, auth, state| async move {
+            users::set_user_disabled(username, false, auth, state).await
+        });
+
+    let reset_user_password = warp::post()
+        .and(warp::path!("api" / "admin" / "users" / String / "password"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(users::reset_password);
+
+    let create_podcast_list = warp::post()
+        .and(warp::path!("api" / "2" / "lists" / String / ..))
+        .and(with_format("create"))
+        .and(warp::path::end())
+        .and(warp::query::<podcast_lists::CreateListQueryParams>())
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::bytes())
+        .and_then(podcast_lists::create_list);
+
+    let get_podcast_lists = warp::get()
+        .and(warp::path!("api" / "2" / "lists" / String))
+        .and(state_filter.clone())
+        .and_then(podcast_lists::get_lists);
+
+    let get_podcast_list = warp::get()
+        .and(warp::path!(
+            "api" / "2" / "lists" / String / "list" / String
+        ))
+        .and(warp::query::<FormatQueryParams>())
+        .and(state_filter.clone())
+        .and_then(podcast_lists::get_list);
+
+    let update_podcast_list = warp::put()
+        .and(warp::path!(
+            "api" / "2" / "lists" / String / "list" / String
+        ))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::bytes())
+        .and_then(podcast_lists::update_list);
+
+    let delete_podcast_list = warp::delete()
+        .and(warp::path!(
+            "api" / "2" / "lists" / String / "list" / String
+        ))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(podcast_lists::delete_list);
+
+    let list_aliases = warp::get()
+        .and(warp::path!("api" / "admin" / "aliases"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(aliases::list_aliases);
+
+    let create_alias = warp::post()
+        .and(warp::path!("api" / "admin" / "aliases"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and(warp::body::json())
+        .and_then(aliases::create_alias);
+
+    let delete_alias = warp::delete()
+        .and(warp::path!("api" / "admin" / "aliases"))
+        .and(warp::query::<aliases::AliasQueryParams>())
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(aliases::delete_alias);
+
+    let normalize_urls = warp::post()
+        .and(warp::path!("api" / "admin" / "urls" / "normalize"))
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(aliases::normalize_urls);
+
+    let toplist = warp::get()
+        .and(warp::path!("toplist" / String))
+        .and(warp::query::<FormatQueryParams>())
+        .and(state_filter.clone())
+        .and_then(directory::toplist);
+
+    let search = warp::get()
+        .and(with_format("search"))
+        .and(warp::path::end())
+        .and(warp::query::<directory::SearchQueryParams>())
+        .and(warp::query::<FormatQueryParams>())
+        .and(state_filter.clone())
+        .and_then(directory::search);
+
+    let search_v2 = warp::get()
+        .and(warp::path!("api" / "2" / ..))
+        .and(with_format("search"))
+        .and(warp::path::end())
+        .and(warp::query::<directory::SearchQueryParams>())
+        .and(warp::query::<FormatQueryParams>())
+        .and(state_filter.clone())
+        .and_then(directory::search);
+
+    let podcast_data = warp::get()
+        .and(warp::path!("api" / "2" / "data" / "podcast.json"))
+        .and(warp::query::<directory::PodcastDataQueryParams>())
+        .and(state_filter.clone())
+        .and_then(directory::podcast_data);
+
+    let episode_data = warp::get()
+        .and(warp::path!("api" / "2" / "data" / "episode.json"))
+        .and(warp::query::<directory::EpisodeDataQueryParams>())
+        .and(state_filter.clone())
+        .and_then(directory::episode_data);
+
+    let top_tags = warp::get()
+        .and(warp::path!("api" / "2" / "tags" / String))
+        .and(state_filter.clone())
+        .and_then(directory::top_tags);
+
+    let podcasts_for_tag = warp::get()
+        .and(warp::path!("api" / "2" / "tag" / String / String))
+        .and(state_filter.clone())
+        .and_then(directory::podcasts_for_tag);
+
+    let podcast_page = warp::get()
+        .and(warp::path!("podcast" / String))
+        .and(state_filter.clone())
+        .and_then(directory::podcast_page);
+
+    let episode_page = warp::get()
+        .and(warp::path!("episode" / String))
+        .and(state_filter.clone())
+        .and_then(directory::episode_page);
+
+    let get_suggestions = warp::get()
+        .and(warp::path!("suggestions" / String))
+        .and(warp::query::<FormatQueryParams>())
+        .and(auth_filter.clone())
+        .and(state_filter.clone())
+        .and_then(suggestions::get_suggestions);
+
+    let directory_api = toplist
+        .or(search)
+        .or(search_v2)
+        .or(podcast_data)
+        .or(episode_data)
+        .or(top_tags)
+        .or(podcasts_for_tag)
+        .or(podcast_page)
+        .or(episode_page)
+        .or(get_suggestions);
+
+    let admin_api = list_users
+        .or(create_user)
+        .or(get_user)
+        .or(delete_user)
+        .or(disable_user)
+        .or(enable_user)
+        .or(reset_user_password)
+        .or(list_aliases)
+        .or(create_alias)
+        .or(delete_alias)
+        .or(normalize_urls);
+
+    let lists_api = create_podcast_list
+        .or(get_podcast_lists)
+        .or(get_podcast_list)
+        .or(update_podcast_list)
+        .or(delete_podcast_list);
+
+    let bookmarks_api = get_bookmarks
+        .or(create_bookmark)
+        .or(update_bookmark)
+        .or(delete_bookmark);
+
+    let sync_api = list_devices
         .or(update_device)
+        .or(delete_device)
+        .or(merge_device)
         .or(get_device_updates)
         .or(get_sync_devices)
         .or(update_sync_devices)
         .or(get_subscriptions)
         .or(upload_subscriptions)
+        .or(get_subscription_history)
         .or(get_episode_actions)
         .or(upload_episode_actions)
+        .or(get_chapters)
+        .or(upload_chapters);
+
+    client_config
+        .or(admin_ui)
+        .or(admin_api)
+        .or(directory_api)
+        .or(gpoddersync_api)
+        .or(login)
+        .or(logout)
+        .or(change_password)
+        .or(delete_account)
+        .or(sync_api)
+        .or(lists_api)
         .or(get_settings)
         .or(save_settings)
         .or(get_favorites)
+        .or(bookmarks_api)
         .or(get_subscriptions_simple)
         .or(get_all_subscriptions_simple)
         .or(upload_subscriptions_simple)
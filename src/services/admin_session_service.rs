@@ -0,0 +1,110 @@
+use crate::{
+    error::{AppError, AppResult},
+    models::User,
+    repository::{AdminSessionRepository, UserRepository},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ADMIN_SESSION_DURATION_SECS: i64 = 12 * 60 * 60; // 12 hours
+
+/// Sessions of the admin web UI, kept apart from the API sessions so that a
+/// gpodder client login never grants access to the dashboard
+#[derive(Clone)]
+pub struct AdminSessionService {
+    session_repo: AdminSessionRepository,
+    user_repo: UserRepository,
+}
+
+impl AdminSessionService {
+    pub fn new(session_repo: AdminSessionRepository, user_repo: UserRepository) -> Self {
+        Self {
+            session_repo,
+            user_repo,
+        }
+    }
+
+    pub fn session_duration_secs() -> i64 {
+        ADMIN_SESSION_DURATION_SECS
+    }
+
+    pub async fn create_session(&self, user_id: i64) -> AppResult<String> {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let current_time = current_timestamp();
+
+        // Opportunistically drop stale sessions on every login
+        self.session_repo
+            .delete_expired(current_time)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        self.session_repo
+            .create(
+                &session_id,
+                user_id,
+                current_time + ADMIN_SESSION_DURATION_SECS,
+            )
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        tracing::info!("Created admin session for user {}", user_id);
+
+        Ok(session_id)
+    }
+
+    /// Resolve a session to its user, failing if the session expired or the
+    /// user was disabled or is no longer an administrator
+    pub async fn validate_session(&self, session_id: &str) -> AppResult<User> {
+        let session = self
+            .session_repo
+            .find_by_id(session_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or(AppError::Authentication)?;
+
+        if session.expires_at < current_timestamp() {
+            self.delete_session(session_id).await?;
+            return Err(AppError::Authentication);
+        }
+
+        let user = self
+            .user_repo
+            .find_by_id(session.user_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or(AppError::Authentication)?;
+
+        if user.disabled {
+            return Err(AppError::Authentication);
+        }
+        if !user.is_admin {
+            return Err(AppError::Authorization);
+        }
+
+        Ok(user)
+    }
+
+    pub async fn delete_session(&self, session_id: &str) -> AppResult<()> {
+        self.session_repo
+            .delete(session_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    pub async fn delete_user_sessions(&self, user_id: i64) -> AppResult<()> {
+        let count = self
+            .session_repo
+            .delete_by_user(user_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        tracing::info!("Deleted {} admin sessions of user {}", count, user_id);
+        Ok(())
+    }
+}
+
+fn current_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
@@ -0,0 +1,130 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{Bookmark, BookmarkMark};
+use crate::repository::BookmarkRepository;
+
+/// Bookmarks point into the episode
+fn validate_position(position: i64) -> AppResult<()> {
+    if position < 0 {
+        return Err(AppError::BadRequest(format!(
+            "Bookmark position {} is negative",
+            position
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct BookmarkService {
+    bookmark_repo: BookmarkRepository,
+}
+
+impl BookmarkService {
+    pub fn new(bookmark_repo: BookmarkRepository) -> Self {
+        Self { bookmark_repo }
+    }
+
+    pub async fn create_bookmark(
+        &self,
+        user_id: i64,
+        podcast_url: &str,
+        episode_url: &str,
+        mark: &BookmarkMark,
+    ) -> AppResult<Bookmark> {
+        validate_position(mark.position)?;
+        Ok(self
+            .bookmark_repo
+            .create(user_id, podcast_url, episode_url, mark)
+            .await?)
+    }
+
+    pub async fn update_bookmark(
+        &self,
+        user_id: i64,
+        id: i64,
+        position: Option<i64>,
+        note: Option<&str>,
+    ) -> AppResult<Bookmark> {
+        if let Some(position) = position {
+            validate_position(position)?;
+        }
+        self.bookmark_repo
+            .update(user_id, id, position, note)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Bookmark {} not found", id)))
+    }
+
+    pub async fn delete_bookmark(&self, user_id: i64, id: i64) -> AppResult<()> {
+        if !self.bookmark_repo.delete(user_id, id).await? {
+            return Err(AppError::NotFound(format!("Bookmark {} not found", id)));
+        }
+        Ok(())
+    }
+
+    /// Current bookmarks and the ids of deleted ones changed after cursor
+    /// `since`; without a cursor only the current bookmarks
+    pub async fn get_bookmarks(
+        &self,
+        user_id: i64,
+        since: Option<i64>,
+        podcast_url: Option<&str>,
+        episode_url: Option<&str>,
+    ) -> AppResult<(Vec<Bookmark>, Vec<i64>)> {
+        let (deleted, current): (Vec<_>, Vec<_>) = self
+            .bookmark_repo
+            .list_since(user_id, since.unwrap_or(0), podcast_url, episode_url)
+            .await?
+            .into_iter()
+            .partition(|bookmark| bookmark.deleted_at.is_some());
+
+        let deleted_ids = match since {
+            Some(_) => deleted.iter().map(|bookmark| bookmark.id).collect(),
+            None => Vec::new(),
+        };
+        Ok((current, deleted_ids))
+    }
+
+    /// Bookmarks of an episode, for the `bookmarks` episode setting
+    pub async fn episode_bookmarks(
+        &self,
+        user_id: i64,
+        episode_url: &str,
+    ) -> AppResult<Vec<BookmarkMark>> {
+        let (bookmarks, _) = self
+            .get_bookmarks(user_id, None, None, Some(episode_url))
+            .await?;
+        Ok(bookmarks.iter().map(Bookmark::to_mark).collect())
+    }
+
+    /// Replaces the bookmarks of an episode, as done by saving the
+    /// `bookmarks` episode setting
+    pub async fn set_episode_bookmarks(
+        &self,
+        user_id: i64,
+        podcast_url: &str,
+        episode_url: &str,
+        marks: &[BookmarkMark],
+    ) -> AppResult<()> {
+        for mark in marks {
+            validate_position(mark.position)?;
+        }
+        self.bookmark_repo
+            .replace_episode(user_id, podcast_url, episode_url, marks)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_position() {
+        assert!(validate_position(0).is_ok());
+        assert!(validate_position(3600).is_ok());
+        assert!(matches!(
+            validate_position(-5),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,23 @@
+use crate::error::AppResult;
+use crate::repository::ChangeLogRepository;
+
+/// Hands out the sync cursors returned as `timestamp` by the sync endpoints
+#[derive(Clone)]
+pub struct ChangeLogService {
+    change_log_repo: ChangeLogRepository,
+}
+
+impl ChangeLogService {
+    pub fn new(change_log_repo: ChangeLogRepository) -> Self {
+        Self { change_log_repo }
+    }
+
+    /// Cursor to hand to a client as its next `since`.
+    ///
+    /// Must be taken before the data of the response is read (or before an
+    /// upload is applied): changes written in between are then sent again
+    /// instead of being skipped.
+    pub async fn current_cursor(&self) -> AppResult<i64> {
+        Ok(self.change_log_repo.current_cursor().await?)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{Chapter, ChapterMark, ChapterRange};
+use crate::repository::ChapterRepository;
+
+/// Chapters start within the episode and do not end before they start
+fn validate_range(start: i64, end: i64) -> Result<(), String> {
+    if start < 0 {
+        return Err(format!("Chapter start {} is negative", start));
+    }
+    if end < start {
+        return Err(format!("Chapter end {} is before its start {}", end, start));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct ChapterService {
+    chapter_repo: ChapterRepository,
+}
+
+impl ChapterService {
+    pub fn new(chapter_repo: ChapterRepository) -> Self {
+        Self { chapter_repo }
+    }
+
+    /// Applies an upload for one episode; chapters listed in both `add` and
+    /// `remove` end up added
+    pub async fn upload_chapters(
+        &self,
+        user_id: i64,
+        podcast_url: &str,
+        episode_url: &str,
+        add: &[ChapterMark],
+        remove: &[ChapterRange],
+    ) -> AppResult<()> {
+        let ranges = add
+            .iter()
+            .map(|mark| (mark.start, mark.end))
+            .chain(remove.iter().map(|range| (range.start, range.end)));
+        for (start, end) in ranges {
+            validate_range(start, end).map_err(AppError::BadRequest)?;
+        }
+
+        self.chapter_repo
+            .apply_changes(user_id, podcast_url, episode_url, add, remove)
+            .await?;
+        Ok(())
+    }
+
+    /// Current and removed chapters changed after cursor `since`; without a
+    /// cursor only the current chapters
+    pub async fn get_chapters(
+        &self,
+        user_id: i64,
+        since: Option<i64>,
+        podcast_url: Option<&str>,
+        episode_url: Option<&str>,
+    ) -> AppResult<(Vec<Chapter>, Vec<Chapter>)> {
+        let (removed, current): (Vec<_>, Vec<_>) = self
+            .chapter_repo
+            .list_since(user_id, since.unwrap_or(0), podcast_url, episode_url)
+            .await?
+            .into_iter()
+            .partition(|chapter| chapter.deleted_at.is_some());
+
+        let removed = match since {
+            Some(_) => removed,
+            None => Vec::new(),
+        };
+        Ok((current, removed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_range() {
+        assert!(validate_range(0, 0).is_ok());
+        assert!(validate_range(10, 95).is_ok());
+        assert!(validate_range(-1, 10).is_err());
+        assert!(validate_range(20, 10).is_err());
+    }
+}
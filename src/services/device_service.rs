@@ -1,8 +1,20 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::{
     error::{AppError, AppResult},
     repository::DeviceRepository,
 };
 
+/// How often devices are checked against the retention period
+const ARCHIVE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Devices last seen before this time are archived
+fn retention_cutoff(now: i64, retention_days: u64) -> i64 {
+    let days = i64::try_from(retention_days).unwrap_or(i64::MAX);
+    now.saturating_sub(days.saturating_mul(24 * 3600))
+}
+
 #[derive(Clone)]
 pub struct DeviceService {
     device_repo: DeviceRepository,
@@ -22,7 +34,7 @@ impl DeviceService {
             .find_by_device_id(user_id, device_id)
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?
-            .ok_or_else(|| AppError::Internal(format!("Device {} not found", device_id)))
+            .ok_or_else(|| AppError::NotFound(format!("Device {} not found", device_id)))
     }
 
     pub async fn get_or_create_device(
@@ -58,4 +70,114 @@ impl DeviceService {
             .await
             .map_err(|e| AppError::Internal(e.to_string()))
     }
+
+    /// Devices of a user including archived ones, for the admin web UI
+    pub async fn list_all_user_devices(
+        &self,
+        user_id: i64,
+    ) -> AppResult<Vec<crate::models::Device>> {
+        self.device_repo
+            .list_all_by_user(user_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    /// Create a device or change caption and type of an existing one
+    pub async fn update_device(
+        &self,
+        user_id: i64,
+        device_id: &str,
+        caption: Option<&str>,
+        device_type: Option<&str>,
+    ) -> AppResult<i64> {
+        match self
+            .device_repo
+            .find_by_device_id(user_id, device_id)
+            .await?
+        {
+            Some(device) => {
+                self.device_repo
+                    .update(device.id, caption, device_type)
+                    .await?;
+                Ok(device.id)
+            }
+            None => {
+                self.get_or_create_device(user_id, device_id, caption, device_type)
+                    .await
+            }
+        }
+    }
+
+    pub async fn delete_device(&self, user_id: i64, device_id: &str) -> AppResult<()> {
+        let device = self.find_by_device_id(user_id, device_id).await?;
+        self.device_repo.delete(user_id, device.id).await?;
+        tracing::info!("Deleted device {} of user {}", device_id, user_id);
+        Ok(())
+    }
+
+    /// Merge device `source` into device `target`, as when a client comes
+    /// back with a regenerated device id. An unknown `target` is a rename.
+    pub async fn merge_devices(&self, user_id: i64, source: &str, target: &str) -> AppResult<()> {
+        if source == target {
+            return Err(AppError::BadRequest(
+                "Cannot merge a device into itself".to_string(),
+            ));
+        }
+        if target.is_empty() {
+            return Err(AppError::BadRequest("Missing target device".to_string()));
+        }
+
+        let source_device = self.find_by_device_id(user_id, source).await?;
+        match self.device_repo.find_by_device_id(user_id, target).await? {
+            Some(target_device) => {
+                self.device_repo
+                    .merge(user_id, source_device.id, target_device.id)
+                    .await?
+            }
+            None => self.device_repo.rename(source_device.id, target).await?,
+        }
+
+        tracing::info!(
+            "Merged device {} into {} for user {}",
+            source,
+            target,
+            user_id
+        );
+        Ok(())
+    }
+
+    /// Record a sync request of the device and the client's user agent
+    pub async fn record_seen(&self, id: i64, user_agent: Option<&str>) -> AppResult<()> {
+        self.device_repo.mark_seen(id, user_agent).await?;
+        Ok(())
+    }
+
+    /// Archives devices that have not been seen for `retention_days`, checking
+    /// every hour
+    pub async fn archive_stale_devices(self: Arc<Self>, retention_days: u64) {
+        let mut interval = tokio::time::interval(ARCHIVE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let cutoff = retention_cutoff(chrono::Utc::now().timestamp(), retention_days);
+            match self.device_repo.archive_unseen(cutoff).await {
+                Ok(0) => {}
+                Ok(archived) => tracing::info!("Archived {} inactive devices", archived),
+                Err(e) => tracing::error!("Archiving inactive devices failed: {:?}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_cutoff() {
+        assert_eq!(retention_cutoff(1_000_000, 1), 1_000_000 - 86_400);
+        assert_eq!(retention_cutoff(1_000_000, 0), 1_000_000);
+        assert_eq!(retention_cutoff(0, u64::MAX), -i64::MAX);
+    }
 }
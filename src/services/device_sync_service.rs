@@ -1,19 +1,25 @@
 use crate::error::{AppError, AppResult};
 use crate::models::DeviceSyncStatus;
-use crate::repository::{DeviceRepository, DeviceSyncRepository};
+use crate::repository::{DeviceRepository, DeviceSyncRepository, SubscriptionRepository};
 use std::collections::{HashMap, HashSet};
 
 #[derive(Clone)]
 pub struct DeviceSyncService {
     device_sync_repo: DeviceSyncRepository,
     device_repo: DeviceRepository,
+    sub_repo: SubscriptionRepository,
 }
 
 impl DeviceSyncService {
-    pub fn new(device_sync_repo: DeviceSyncRepository, device_repo: DeviceRepository) -> Self {
+    pub fn new(
+        device_sync_repo: DeviceSyncRepository,
+        device_repo: DeviceRepository,
+        sub_repo: SubscriptionRepository,
+    ) -> Self {
         Self {
             device_sync_repo,
             device_repo,
+            sub_repo,
         }
     }
 
@@ -156,6 +162,13 @@ impl DeviceSyncService {
                     .add_device_to_group(target_group_id, device_db_id)
                     .await?;
             }
+
+            // Bring all members of the group to the union of their subscriptions
+            let group_devices = self
+                .device_sync_repo
+                .get_group_devices(target_group_id)
+                .await?;
+            self.sub_repo.merge_devices(user_id, &group_devices).await?;
         }
 
         // Return updated sync status
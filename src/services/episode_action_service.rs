@@ -4,6 +4,51 @@ use crate::{
     repository::{EpisodeActionRepository, EpisodeActionWithDevice},
 };
 
+/// Actions defined by the gpodder API
+const VALID_ACTIONS: [&str; 4] = ["download", "play", "delete", "new"];
+
+/// Checks an episode action against the gpodder API rules: a known action,
+/// playback positions only on `play`, non-negative and ordered as
+/// `started <= position <= total`
+pub fn validate_action(action: &EpisodeAction) -> Result<(), String> {
+    if !VALID_ACTIONS.contains(&action.action.as_str()) {
+        return Err(format!("Invalid action '{}'", action.action));
+    }
+
+    let positions = [
+        ("started", action.started),
+        ("position", action.position),
+        ("total", action.total),
+    ];
+
+    if action.action != "play" {
+        if let Some((name, _)) = positions.iter().find(|(_, value)| value.is_some()) {
+            return Err(format!("'{}' is only allowed on play actions", name));
+        }
+        return Ok(());
+    }
+
+    if let Some((name, _)) = positions
+        .iter()
+        .find(|(_, value)| value.is_some_and(|v| v < 0))
+    {
+        return Err(format!("'{}' must not be negative", name));
+    }
+
+    if let (Some(started), Some(position)) = (action.started, action.position) {
+        if started > position {
+            return Err("'started' must not be after 'position'".to_string());
+        }
+    }
+    if let (Some(position), Some(total)) = (action.position, action.total) {
+        if position > total {
+            return Err("'position' must not be after 'total'".to_string());
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct EpisodeActionService {
     action_repo: EpisodeActionRepository,
@@ -25,21 +70,13 @@ impl EpisodeActionService {
             .map_err(|e| AppError::Internal(e.to_string()))
     }
 
-    pub async fn get_actions_since(
+    pub async fn get_recent_actions(
         &self,
         user_id: i64,
-        device_id: Option<i64>,
-        podcast_url: Option<String>,
-        since: i64,
+        limit: i64,
     ) -> AppResult<Vec<EpisodeActionWithDevice>> {
-        let query = EpisodeActionQuery {
-            since: Some(since),
-            podcast: podcast_url,
-            device: device_id.map(|id| id.to_string()),
-            aggregated: None,
-        };
         self.action_repo
-            .list(user_id, query)
+            .list_recent(user_id, limit)
             .await
             .map_err(|e| AppError::Internal(e.to_string()))
     }
@@ -54,3 +91,56 @@ impl EpisodeActionService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(
+        name: &str,
+        started: Option<i64>,
+        position: Option<i64>,
+        total: Option<i64>,
+    ) -> EpisodeAction {
+        EpisodeAction {
+            id: 0,
+            user_id: 1,
+            device_id: 1,
+            podcast_url: "https://example.com/feed.xml".to_string(),
+            episode_url: "https://example.com/1.mp3".to_string(),
+            guid: None,
+            action: name.to_string(),
+            timestamp: 0,
+            started,
+            position,
+            total,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_validate_action_accepts_valid_actions() {
+        assert!(validate_action(&action("download", None, None, None)).is_ok());
+        assert!(validate_action(&action("new", None, None, None)).is_ok());
+        assert!(validate_action(&action("delete", None, None, None)).is_ok());
+        assert!(validate_action(&action("play", Some(0), Some(60), Some(600))).is_ok());
+        assert!(validate_action(&action("play", None, Some(60), None)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_action_rejects_unknown_action() {
+        assert!(validate_action(&action("flattr", None, None, None)).is_err());
+    }
+
+    #[test]
+    fn test_validate_action_positions_only_on_play() {
+        assert!(validate_action(&action("download", None, Some(10), None)).is_err());
+    }
+
+    #[test]
+    fn test_validate_action_positions_ordered_and_non_negative() {
+        assert!(validate_action(&action("play", Some(-1), Some(10), None)).is_err());
+        assert!(validate_action(&action("play", Some(20), Some(10), None)).is_err());
+        assert!(validate_action(&action("play", None, Some(700), Some(600))).is_err());
+    }
+}
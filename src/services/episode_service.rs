@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{Episode, EpisodeData, EpisodeUpdate};
+use crate::repository::{EpisodeActionRepository, EpisodeRepository};
+use crate::utils::fts_query;
+
+/// Upper bound of new episodes returned by one `/api/2/updates` request
+const MAX_EPISODE_UPDATES: i64 = 200;
+
+#[derive(Clone)]
+pub struct EpisodeService {
+    episode_repo: EpisodeRepository,
+    action_repo: EpisodeActionRepository,
+    config: Config,
+}
+
+impl EpisodeService {
+    pub fn new(
+        episode_repo: EpisodeRepository,
+        action_repo: EpisodeActionRepository,
+        config: Config,
+    ) -> Self {
+        Self {
+            episode_repo,
+            action_repo,
+            config,
+        }
+    }
+
+    /// Catalogued episodes keyed by their media URL
+    pub async fn get_by_urls(&self, urls: &[String]) -> AppResult<HashMap<String, Episode>> {
+        let episodes = self
+            .episode_repo
+            .get_by_urls(urls)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        Ok(episodes
+            .into_iter()
+            .map(|episode| (episode.url.clone(), episode))
+            .collect())
+    }
+
+    /// Directory data of a catalogued episode
+    pub async fn get_episode(
+        &self,
+        podcast_url: Option<&str>,
+        url: &str,
+    ) -> AppResult<EpisodeData> {
+        self.episode_repo
+            .find(podcast_url, url)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .map(|episode| episode.to_data(&self.config.base_url))
+            .ok_or_else(|| AppError::NotFound(format!("Episode '{}' not found", url)))
+    }
+
+    /// Full-text search over episode titles and descriptions
+    pub async fn search(
+        &self,
+        query: &str,
+        podcast_url: Option<&str>,
+        limit: i64,
+    ) -> AppResult<Vec<EpisodeData>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let episodes = self
+            .episode_repo
+            .search(&fts_query, podcast_url, limit)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        Ok(episodes
+            .iter()
+            .map(|episode| episode.to_data(&self.config.base_url))
+            .collect())
+    }
+
+    /// Episodes of the given podcasts catalogued after the change cursor
+    /// `since`, with the user's latest action on each of them
+    pub async fn get_episode_updates(
+        &self,
+        user_id: i64,
+        podcast_urls: &[String],
+        since: i64,
+        include_actions: bool,
+    ) -> AppResult<Vec<EpisodeUpdate>> {
+        let episodes = self
+            .episode_repo
+            .list_added_since(podcast_urls, since, MAX_EPISODE_UPDATES)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let episode_urls: Vec<String> = episodes.iter().map(|e| e.url.clone()).collect();
+        let mut latest_actions: HashMap<_, _> = self
+            .action_repo
+            .latest_for_episodes(user_id, &episode_urls)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .into_iter()
+            .map(|action| (action.episode_url.clone(), action))
+            .collect();
+
+        Ok(episodes
+            .iter()
+            .map(|episode| {
+                let mut update = episode.to_update(&self.config.base_url);
+                if let Some(action) = latest_actions.remove(&episode.url) {
+                    update.status = action.action.clone();
+                    if include_actions {
+                        update.action = Some(serde_json::json!({
+                            "podcast": action.podcast_url,
+                            "episode": action.episode_url,
+                            "device": action.device,
+                            "action": action.action,
+                            "timestamp": action.timestamp,
+                            "started": action.started,
+                            "position": action.position,
+                            "total": action.total,
+                        }));
+                    }
+                }
+                update
+            })
+            .collect())
+    }
+}
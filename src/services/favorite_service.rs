@@ -1,15 +1,19 @@
 use crate::error::AppResult;
 use crate::models::{FavoriteMetadata, FavoriteResponse};
-use crate::repository::FavoriteRepository;
+use crate::repository::{EpisodeRepository, FavoriteRepository};
 
 #[derive(Clone)]
 pub struct FavoriteService {
     favorite_repo: FavoriteRepository,
+    episode_repo: EpisodeRepository,
 }
 
 impl FavoriteService {
-    pub fn new(favorite_repo: FavoriteRepository) -> Self {
-        Self { favorite_repo }
+    pub fn new(favorite_repo: FavoriteRepository, episode_repo: EpisodeRepository) -> Self {
+        Self {
+            favorite_repo,
+            episode_repo,
+        }
     }
 
     /// Add an episode to favorites
@@ -37,8 +41,26 @@ impl FavoriteService {
         base_url: &str,
     ) -> AppResult<Vec<FavoriteResponse>> {
         let favorites = self.favorite_repo.get_user_favorites(user_id).await?;
-        let responses: Vec<FavoriteResponse> =
-            favorites.iter().map(|f| f.to_response(base_url)).collect();
+
+        let episode_urls: Vec<String> = favorites.iter().map(|f| f.episode_url.clone()).collect();
+        let episodes: std::collections::HashMap<_, _> = self
+            .episode_repo
+            .get_by_urls(&episode_urls)
+            .await?
+            .into_iter()
+            .map(|episode| (episode.url.clone(), episode))
+            .collect();
+
+        let responses: Vec<FavoriteResponse> = favorites
+            .iter()
+            .map(|f| {
+                let mut response = f.to_response(base_url);
+                if let Some(episode) = episodes.get(&f.episode_url) {
+                    response.fill_from(episode);
+                }
+                response
+            })
+            .collect();
         Ok(responses)
     }
 
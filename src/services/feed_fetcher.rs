@@ -0,0 +1,675 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Empty, Limited};
+use hyper::{body::Incoming, header, Request, Response, StatusCode, Uri};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::client::legacy::{connect::HttpConnector, Client};
+use hyper_util::rt::TokioExecutor;
+
+use crate::error::AppResult;
+use crate::models::{EpisodeMetadata, FeedFetchState};
+use crate::repository::{EpisodeRepository, PodcastRepository};
+use crate::services::UrlService;
+use crate::utils::{parse_feed, ParsedFeed};
+
+const USER_AGENT: &str = concat!("PodSynq/", env!("CARGO_PKG_VERSION"));
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_REDIRECTS: usize = 5;
+const MAX_FEED_SIZE: usize = 10 * 1024 * 1024;
+/// Upper bound of feeds refreshed in one run, the rest waits for the next one
+const MAX_FEEDS_PER_RUN: i64 = 500;
+const BACKOFF_BASE: Duration = Duration::from_secs(60);
+const BACKOFF_MAX: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Why a feed could not be fetched
+#[derive(Debug)]
+enum FetchError {
+    /// The server itself is unavailable or overloaded; all feeds of the host back off
+    Host {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// Only this feed is broken (e.g. 404 or invalid XML)
+    Feed(String),
+}
+
+enum FetchOutcome {
+    NotModified,
+    Fetched {
+        body: Bytes,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+}
+
+/// Response of the final request and the URL the feed permanently moved to,
+/// if every redirect on the way was permanent
+struct Redirected {
+    response: Response<Incoming>,
+    moved_to: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RefreshSummary {
+    pub updated: usize,
+    pub not_modified: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+struct HostBackoff {
+    failures: u32,
+    retry_at: Instant,
+}
+
+/// Periodically downloads subscribed feeds and stores their channel metadata
+/// and episodes
+pub struct FeedFetcher {
+    podcast_repo: PodcastRepository,
+    episode_repo: EpisodeRepository,
+    client: Client<HttpsConnector<HttpConnector>, Empty<Bytes>>,
+    /// Normalizes episode URLs and records feeds that moved
+    url_service: UrlService,
+    refresh_interval: Duration,
+    backoff: Mutex<HashMap<String, HostBackoff>>,
+}
+
+impl FeedFetcher {
+    pub fn new(
+        podcast_repo: PodcastRepository,
+        episode_repo: EpisodeRepository,
+        url_service: UrlService,
+        refresh_interval: Duration,
+    ) -> Self {
+        let connector = HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .enable_http2()
+            .build();
+
+        Self {
+            podcast_repo,
+            episode_repo,
+            url_service,
+            client: Client::builder(TokioExecutor::new()).build(connector),
+            refresh_interval,
+            backoff: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Refresh due feeds forever, once per refresh interval
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.refresh_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match self.refresh_due_feeds().await {
+                Ok(summary) => tracing::info!("Feed refresh finished: {:?}", summary),
+                Err(e) => tracing::error!("Feed refresh failed: {:?}", e),
+            }
+        }
+    }
+
+    /// Fetch every subscribed feed that was not fetched within the refresh interval
+    pub async fn refresh_due_feeds(&self) -> AppResult<RefreshSummary> {
+        let fetched_before =
+            chrono::Utc::now().timestamp() - self.refresh_interval.as_secs() as i64;
+        let feeds = self
+            .podcast_repo
+            .list_due_for_refresh(fetched_before, MAX_FEEDS_PER_RUN)
+            .await?;
+
+        let mut summary = RefreshSummary::default();
+
+        for feed in feeds {
+            let Ok(uri) = feed.url.parse::<Uri>() else {
+                tracing::debug!("Skipping feed with invalid URL: {}", feed.url);
+                self.podcast_repo.mark_fetched(&feed.url).await?;
+                summary.failed += 1;
+                continue;
+            };
+            let host = uri.authority().map(|a| a.to_string()).unwrap_or_default();
+
+            if self.is_backing_off(&host) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            // Where the feed moved to, and what told us
+            let moved = match self.fetch(uri, &feed).await {
+                Ok((FetchOutcome::NotModified, moved_to)) => {
+                    self.reset_backoff(&host);
+                    self.podcast_repo.mark_fetched(&feed.url).await?;
+                    summary.not_modified += 1;
+                    moved_to.map(|url| (url, "redirect"))
+                }
+                Ok((
+                    FetchOutcome::Fetched {
+                        body,
+                        etag,
+                        last_modified,
+                    },
+                    moved_to,
+                )) => {
+                    self.reset_backoff(&host);
+                    if let Some(parsed) = self.store_feed(&feed.url, &body).await? {
+                        self.podcast_repo
+                            .record_fetch(&feed.url, etag.as_deref(), last_modified.as_deref())
+                            .await?;
+                        summary.updated += 1;
+                        // The publisher's statement wins over where the server sent us
+                        parsed
+                            .new_feed_url
+                            .map(|url| (url, "new-feed-url"))
+                            .or(moved_to.map(|url| (url, "redirect")))
+                    } else {
+                        self.podcast_repo.mark_fetched(&feed.url).await?;
+                        summary.failed += 1;
+                        None
+                    }
+                }
+                Err(FetchError::Host {
+                    message,
+                    retry_after,
+                }) => {
+                    tracing::warn!("Fetching {} failed: {}", feed.url, message);
+                    self.register_failure(&host, retry_after);
+                    summary.failed += 1;
+                    None
+                }
+                Err(FetchError::Feed(message)) => {
+                    tracing::warn!("Fetching {} failed: {}", feed.url, message);
+                    self.podcast_repo.mark_fetched(&feed.url).await?;
+                    summary.failed += 1;
+                    None
+                }
+            };
+
+            if let Some((new_url, source)) = moved {
+                match self
+                    .url_service
+                    .move_podcast(&feed.url, &new_url, source)
+                    .await
+                {
+                    Ok(true) => tracing::info!("Feed {} moved to {}", feed.url, new_url),
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("Could not move {} to {}: {}", feed.url, new_url, e),
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Parse a feed document and store its metadata and episodes.
+    /// Returns None for invalid feeds.
+    async fn store_feed(&self, url: &str, body: &[u8]) -> AppResult<Option<ParsedFeed>> {
+        let parsed = match parse_feed(body) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                tracing::warn!("Could not parse feed {}: {}", url, e);
+                return Ok(None);
+            }
+        };
+
+        self.podcast_repo
+            .upsert(
+                url,
+                parsed.title.as_deref(),
+                parsed.description.as_deref(),
+                parsed.website.as_deref(),
+                parsed.logo_url.as_deref(),
+            )
+            .await?;
+        self.podcast_repo.set_tags(url, &parsed.categories).await?;
+
+        // Stored in the canonical form clients' episode actions are stored in
+        let episode_urls: Vec<String> = parsed
+            .episodes
+            .iter()
+            .map(|episode| {
+                let url = self.url_service.sanitize_url(&episode.url);
+                if url.is_empty() {
+                    episode.url.clone()
+                } else {
+                    url
+                }
+            })
+            .collect();
+
+        let episodes: Vec<EpisodeMetadata> = parsed
+            .episodes
+            .iter()
+            .zip(&episode_urls)
+            .map(|(episode, url)| EpisodeMetadata {
+                guid: &episode.guid,
+                url,
+                title: episode.title.as_deref(),
+                description: episode.description.as_deref(),
+                website: episode.website.as_deref(),
+                duration: episode.duration,
+                released: episode.released,
+            })
+            .collect();
+        self.episode_repo.upsert_for_podcast(url, &episodes).await?;
+
+        Ok(Some(parsed))
+    }
+
+    /// Fetch a feed, along with the URL it permanently moved to
+    async fn fetch(
+        &self,
+        uri: Uri,
+        feed: &FeedFetchState,
+    ) -> Result<(FetchOutcome, Option<String>), FetchError> {
+        let Redirected { response, moved_to } = self.get_following_redirects(uri, feed).await?;
+        let status = response.status();
+
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok((FetchOutcome::NotModified, moved_to));
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            let retry_after = response
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+                .map(Duration::from_secs);
+            return Err(FetchError::Host {
+                message: format!("HTTP {}", status),
+                retry_after,
+            });
+        }
+
+        if !status.is_success() {
+            return Err(FetchError::Feed(format!("HTTP {}", status)));
+        }
+
+        let header_value = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &header::HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header_value(header::ETAG);
+        let last_modified = header_value(header::LAST_MODIFIED);
+
+        let body = tokio::time::timeout(
+            REQUEST_TIMEOUT,
+            Limited::new(response.into_body(), MAX_FEED_SIZE).collect(),
+        )
+        .await
+        .map_err(|_| FetchError::Feed("Timed out reading the feed".to_string()))?
+        .map_err(|e| FetchError::Feed(format!("Could not read the feed: {}", e)))?
+        .to_bytes();
+
+        Ok((
+            FetchOutcome::Fetched {
+                body,
+                etag,
+                last_modified,
+            },
+            moved_to,
+        ))
+    }
+
+    async fn get_following_redirects(
+        &self,
+        mut uri: Uri,
+        feed: &FeedFetchState,
+    ) -> Result<Redirected, FetchError> {
+        let mut moved_to = None;
+        let mut permanent = true;
+
+        for _ in 0..=MAX_REDIRECTS {
+            let mut request = Request::get(uri.clone()).header(header::USER_AGENT, USER_AGENT);
+            if let Some(etag) = &feed.etag {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &feed.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+            let request = request
+                .body(Empty::new())
+                .map_err(|e| FetchError::Feed(e.to_string()))?;
+
+            let response = tokio::time::timeout(REQUEST_TIMEOUT, self.client.request(request))
+                .await
+                .map_err(|_| FetchError::Host {
+                    message: "Request timed out".to_string(),
+                    retry_after: None,
+                })?
+                .map_err(|e| FetchError::Host {
+                    message: e.to_string(),
+                    retry_after: None,
+                })?;
+
+            if !response.status().is_redirection() || response.status() == StatusCode::NOT_MODIFIED
+            {
+                return Ok(Redirected { response, moved_to });
+            }
+
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| FetchError::Feed("Redirect without location".to_string()))?;
+            uri = resolve_location(&uri, location)
+                .ok_or_else(|| FetchError::Feed(format!("Invalid redirect to {}", location)))?;
+
+            // A temporary hop anywhere in the chain means the old URL stays valid
+            permanent &= matches!(
+                response.status(),
+                StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+            );
+            if permanent {
+                moved_to = Some(uri.to_string());
+            }
+        }
+
+        Err(FetchError::Feed("Too many redirects".to_string()))
+    }
+
+    fn is_backing_off(&self, host: &str) -> bool {
+        let backoff = self.backoff.lock().unwrap();
+        backoff
+            .get(host)
+            .is_some_and(|entry| entry.retry_at > Instant::now())
+    }
+
+    fn reset_backoff(&self, host: &str) {
+        self.backoff.lock().unwrap().remove(host);
+    }
+
+    /// Exponential backoff per host, honoring `Retry-After` when given
+    fn register_failure(&self, host: &str, retry_after: Option<Duration>) {
+        let mut backoff = self.backoff.lock().unwrap();
+        let entry = backoff.entry(host.to_string()).or_insert(HostBackoff {
+            failures: 0,
+            retry_at: Instant::now(),
+        });
+        entry.failures += 1;
+
+        let delay = retry_after
+            .unwrap_or_else(|| BACKOFF_BASE.saturating_mul(1 << (entry.failures - 1).min(16)))
+            .min(BACKOFF_MAX);
+        entry.retry_at = Instant::now() + delay;
+
+        tracing::debug!(
+            "Backing off host {} for {:?} after {} failures",
+            host,
+            delay,
+            entry.failures
+        );
+    }
+}
+
+/// Resolve a `Location` header against the URI of the redirecting request
+fn resolve_location(base: &Uri, location: &str) -> Option<Uri> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        return location.parse().ok();
+    }
+
+    let scheme = base.scheme_str()?;
+    let authority = base.authority()?;
+    if location.starts_with("//") {
+        return format!("{}:{}", scheme, location).parse().ok();
+    }
+
+    let path = if location.starts_with('/') {
+        location.to_string()
+    } else {
+        let base_path = base.path();
+        let dir = &base_path[..base_path.rfind('/').map_or(0, |i| i + 1)];
+        format!("{}{}", dir, location)
+    };
+    format!("{}://{}{}", scheme, authority, path).parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{test_db, UrlRepository};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const FEED: &str = r#"<rss><channel>
+        <title>Stand-in Show</title>
+        <description>Served locally</description>
+        <link>https://example.com/</link>
+        <image><url>https://example.com/logo.png</url></image>
+        <item>
+          <guid>episode-1</guid>
+          <title>First Episode</title>
+          <enclosure url="https://example.com/1.mp3" type="audio/mpeg"/>
+        </item>
+    </channel></rss>"#;
+
+    /// Minimal HTTP server answering every request through `respond`
+    async fn stand_in_server(
+        respond: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = vec![0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                let response = respond(&request);
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    fn fetcher(pool: &sqlx::SqlitePool) -> FeedFetcher {
+        FeedFetcher::new(
+            PodcastRepository::new(pool.clone()),
+            EpisodeRepository::new(pool.clone()),
+            UrlService::new(UrlRepository::new(pool.clone()), Vec::new()),
+            Duration::from_secs(3600),
+        )
+    }
+
+    async fn add_subscribed_podcast(pool: &sqlx::SqlitePool, url: &str) {
+        sqlx::query("INSERT INTO podcasts (url, subscriber_count) VALUES (?, 1)")
+            .bind(url)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn make_due(pool: &sqlx::SqlitePool) {
+        sqlx::query("UPDATE podcasts SET last_fetched_at = 0")
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetches_metadata_and_revalidates_with_etag() {
+        let (base, requests) = stand_in_server(|request| {
+            if request.contains("if-none-match: \"v1\"") {
+                "HTTP/1.1 304 Not Modified\r\nContent-Length: 0\r\n\r\n".to_string()
+            } else {
+                format!(
+                    "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\n\r\n{}",
+                    FEED.len(),
+                    FEED
+                )
+            }
+        })
+        .await;
+        let (pool, _dir) = test_db().await;
+        let url = format!("{}/feed.xml", base);
+        add_subscribed_podcast(&pool, &url).await;
+
+        let fetcher = fetcher(&pool);
+        let repo = PodcastRepository::new(pool.clone());
+
+        let summary = fetcher.refresh_due_feeds().await.unwrap();
+        assert_eq!(summary.updated, 1);
+
+        let podcast = repo.get_by_url(&url).await.unwrap().unwrap();
+        assert_eq!(podcast.title.as_deref(), Some("Stand-in Show"));
+        assert_eq!(podcast.description.as_deref(), Some("Served locally"));
+        assert_eq!(podcast.website.as_deref(), Some("https://example.com/"));
+        assert_eq!(
+            podcast.logo_url.as_deref(),
+            Some("https://example.com/logo.png")
+        );
+
+        let episodes = EpisodeRepository::new(pool.clone())
+            .get_by_urls(&["https://example.com/1.mp3".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(episodes.len(), 1);
+        assert_eq!(episodes[0].guid, "episode-1");
+        assert_eq!(episodes[0].title.as_deref(), Some("First Episode"));
+        assert_eq!(episodes[0].podcast_title.as_deref(), Some("Stand-in Show"));
+
+        // Fresh feeds are not fetched again within the interval
+        let summary = fetcher.refresh_due_feeds().await.unwrap();
+        assert_eq!(summary, RefreshSummary::default());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        make_due(&pool).await;
+        let summary = fetcher.refresh_due_feeds().await.unwrap();
+        assert_eq!(summary.not_modified, 1);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_backs_off_failing_hosts() {
+        let (base, requests) = stand_in_server(|_| {
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n".to_string()
+        })
+        .await;
+        let (pool, _dir) = test_db().await;
+        add_subscribed_podcast(&pool, &format!("{}/a.xml", base)).await;
+        add_subscribed_podcast(&pool, &format!("{}/b.xml", base)).await;
+
+        let fetcher = fetcher(&pool);
+
+        // The second feed of the host is skipped after the first one failed
+        let summary = fetcher.refresh_due_feeds().await.unwrap();
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.skipped, 1);
+
+        make_due(&pool).await;
+        let summary = fetcher.refresh_due_feeds().await.unwrap();
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_follows_permanently_moved_feeds() {
+        let (base, _) = stand_in_server(|request| {
+            if request.starts_with("get /old.xml") {
+                "HTTP/1.1 301 Moved Permanently\r\nLocation: /new.xml\r\nContent-Length: 0\r\n\r\n"
+                    .to_string()
+            } else if request.starts_with("get /temporary.xml") {
+                "HTTP/1.1 302 Found\r\nLocation: /new.xml\r\nContent-Length: 0\r\n\r\n".to_string()
+            } else {
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                    FEED.len(),
+                    FEED
+                )
+            }
+        })
+        .await;
+        let (pool, _dir) = test_db().await;
+        let old = format!("{}/old.xml", base);
+        let new = format!("{}/new.xml", base);
+        let temporary = format!("{}/temporary.xml", base);
+        add_subscribed_podcast(&pool, &old).await;
+        add_subscribed_podcast(&pool, &temporary).await;
+
+        let summary = fetcher(&pool).refresh_due_feeds().await.unwrap();
+        assert_eq!(summary.updated, 2);
+
+        let podcasts = PodcastRepository::new(pool.clone());
+        assert!(podcasts.get_by_url(&old).await.unwrap().is_none());
+        assert!(podcasts.get_by_url(&new).await.unwrap().is_some());
+        assert!(podcasts.get_by_url(&temporary).await.unwrap().is_some());
+
+        let urls = UrlRepository::new(pool.clone());
+        assert_eq!(urls.resolve_alias(&old).await.unwrap(), Some(new));
+        assert_eq!(urls.resolve_alias(&temporary).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_follows_new_feed_url() {
+        let (base, _) = stand_in_server(|_| {
+            let feed = FEED.replace(
+                "<title>Stand-in Show</title>",
+                "<title>Stand-in Show</title>\n<itunes:new-feed-url>https://new.example.com/feed.xml</itunes:new-feed-url>",
+            );
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                feed.len(),
+                feed
+            )
+        })
+        .await;
+        let (pool, _dir) = test_db().await;
+        let old = format!("{}/feed.xml", base);
+        add_subscribed_podcast(&pool, &old).await;
+
+        fetcher(&pool).refresh_due_feeds().await.unwrap();
+
+        let podcast = PodcastRepository::new(pool.clone())
+            .get_by_url("https://new.example.com/feed.xml")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(podcast.title.as_deref(), Some("Stand-in Show"));
+        assert_eq!(
+            UrlRepository::new(pool.clone())
+                .resolve_alias(&old)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("https://new.example.com/feed.xml")
+        );
+    }
+
+    #[test]
+    fn test_resolve_location() {
+        let base: Uri = "https://example.com/feeds/show.xml".parse().unwrap();
+        assert_eq!(
+            resolve_location(&base, "/new.xml").unwrap().to_string(),
+            "https://example.com/new.xml"
+        );
+        assert_eq!(
+            resolve_location(&base, "other.xml").unwrap().to_string(),
+            "https://example.com/feeds/other.xml"
+        );
+        assert_eq!(
+            resolve_location(&base, "http://cdn.example.org/x.xml")
+                .unwrap()
+                .to_string(),
+            "http://cdn.example.org/x.xml"
+        );
+    }
+}
@@ -1,19 +1,35 @@
+pub mod admin_session_service;
+pub mod bookmark_service;
+pub mod change_log_service;
+pub mod chapter_service;
 pub mod device_service;
 pub mod device_sync_service;
 pub mod episode_action_service;
+pub mod episode_service;
 pub mod favorite_service;
+pub mod feed_fetcher;
+pub mod podcast_list_service;
 pub mod podcast_service;
 pub mod session_service;
 pub mod setting_service;
 pub mod subscription_service;
+pub mod url_service;
 pub mod user_service;
 
+pub use admin_session_service::AdminSessionService;
+pub use bookmark_service::BookmarkService;
+pub use change_log_service::ChangeLogService;
+pub use chapter_service::ChapterService;
 pub use device_service::DeviceService;
 pub use device_sync_service::DeviceSyncService;
-pub use episode_action_service::EpisodeActionService;
+pub use episode_action_service::{validate_action, EpisodeActionService};
+pub use episode_service::EpisodeService;
 pub use favorite_service::FavoriteService;
+pub use feed_fetcher::FeedFetcher;
+pub use podcast_list_service::PodcastListService;
 pub use podcast_service::PodcastService;
 pub use session_service::SessionService;
 pub use setting_service::SettingService;
 pub use subscription_service::SubscriptionService;
+pub use url_service::UrlService;
 pub use user_service::UserService;
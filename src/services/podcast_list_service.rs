@@ -0,0 +1,124 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{PodcastList, PodcastListResponse};
+use crate::repository::PodcastListRepository;
+
+/// URL-safe list name of a title: lower-case ASCII letters and digits,
+/// everything else collapsed into single dashes
+fn list_name(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Uploaded URLs without duplicates, in upload order
+fn dedup_urls(podcast_urls: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    podcast_urls
+        .into_iter()
+        .filter(|url| seen.insert(url.clone()))
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct PodcastListService {
+    list_repo: PodcastListRepository,
+    config: Config,
+}
+
+impl PodcastListService {
+    pub fn new(list_repo: PodcastListRepository, config: Config) -> Self {
+        Self { list_repo, config }
+    }
+
+    pub async fn create_list(
+        &self,
+        user_id: i64,
+        title: &str,
+        podcast_urls: Vec<String>,
+    ) -> AppResult<PodcastList> {
+        let title = title.trim();
+        let name = list_name(title);
+        if name.is_empty() {
+            return Err(AppError::BadRequest(
+                "A list title with at least one letter or digit is required".to_string(),
+            ));
+        }
+
+        self.list_repo
+            .create(user_id, &name, title, &dedup_urls(podcast_urls))
+            .await?
+            .ok_or_else(|| AppError::Conflict(format!("List '{}' already exists", name)))
+    }
+
+    pub async fn list_lists(
+        &self,
+        user_id: i64,
+        username: &str,
+    ) -> AppResult<Vec<PodcastListResponse>> {
+        let lists = self.list_repo.list_by_user(user_id).await?;
+        Ok(lists
+            .iter()
+            .map(|list| list.to_response(&self.config.base_url, username))
+            .collect())
+    }
+
+    /// A list and its podcast URLs
+    pub async fn get_list(
+        &self,
+        user_id: i64,
+        name: &str,
+    ) -> AppResult<(PodcastList, Vec<String>)> {
+        let list = self.find_list(user_id, name).await?;
+        let podcast_urls = self.list_repo.podcast_urls(list.id).await?;
+        Ok((list, podcast_urls))
+    }
+
+    pub async fn update_list(
+        &self,
+        user_id: i64,
+        name: &str,
+        podcast_urls: Vec<String>,
+    ) -> AppResult<()> {
+        let list = self.find_list(user_id, name).await?;
+        self.list_repo
+            .set_podcasts(list.id, &dedup_urls(podcast_urls))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_list(&self, user_id: i64, name: &str) -> AppResult<()> {
+        let list = self.find_list(user_id, name).await?;
+        self.list_repo.delete(list.id).await?;
+        Ok(())
+    }
+
+    async fn find_list(&self, user_id: i64, name: &str) -> AppResult<PodcastList> {
+        self.list_repo
+            .find(user_id, name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("List '{}' not found", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_name() {
+        assert_eq!(list_name("My Favourite Shows"), "my-favourite-shows");
+        assert_eq!(list_name("  Tech & Science!  "), "tech-science");
+        assert_eq!(list_name("Café Talk 2"), "caf-talk-2");
+        assert_eq!(list_name("!!!"), "");
+    }
+
+    #[test]
+    fn test_dedup_urls_keeps_first_occurrence() {
+        let urls = vec!["b".to_string(), "a".to_string(), "b".to_string()];
+        assert_eq!(dedup_urls(urls), vec!["b", "a"]);
+    }
+}
@@ -1,9 +1,11 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::config::Config;
-use crate::error::AppResult;
-use crate::models::PodcastMetadata;
+use crate::error::{AppError, AppResult};
+use crate::models::{PodcastMetadata, TagResponse};
 use crate::repository::PodcastRepository;
+use crate::utils::fts_query;
 
 pub struct PodcastService {
     podcast_repo: Arc<PodcastRepository>,
@@ -41,6 +43,87 @@ impl PodcastService {
         Ok(result)
     }
 
+    /// Directory data of a single podcast
+    pub async fn get_podcast(&self, url: &str) -> AppResult<PodcastMetadata> {
+        self.podcast_repo
+            .get_by_url(url)
+            .await?
+            .map(|podcast| podcast.to_metadata(&self.config.base_url))
+            .ok_or_else(|| AppError::NotFound(format!("Podcast '{}' not found", url)))
+    }
+
+    /// Most subscribed podcasts
+    pub async fn toplist(&self, count: i64) -> AppResult<Vec<PodcastMetadata>> {
+        let podcasts = self.podcast_repo.toplist(count).await?;
+        Ok(podcasts
+            .iter()
+            .map(|p| p.to_metadata(&self.config.base_url))
+            .collect())
+    }
+
+    /// Full-text search with prefix matching, ranked by relevance
+    pub async fn search(&self, query: &str, limit: i64) -> AppResult<Vec<PodcastMetadata>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let podcasts = self.podcast_repo.search(&fts_query, limit).await?;
+        Ok(podcasts
+            .iter()
+            .map(|p| p.to_metadata(&self.config.base_url))
+            .collect())
+    }
+
+    pub async fn top_tags(&self, count: i64) -> AppResult<Vec<TagResponse>> {
+        let tags = self.podcast_repo.top_tags(count).await?;
+        Ok(tags
+            .into_iter()
+            .map(|(tag, usage)| TagResponse {
+                title: tag.clone(),
+                tag,
+                usage,
+            })
+            .collect())
+    }
+
+    pub async fn podcasts_for_tag(&self, tag: &str, count: i64) -> AppResult<Vec<PodcastMetadata>> {
+        let podcasts = self
+            .podcast_repo
+            .list_by_tag(&tag.trim().to_lowercase(), count)
+            .await?;
+        Ok(podcasts
+            .iter()
+            .map(|p| p.to_metadata(&self.config.base_url))
+            .collect())
+    }
+
+    /// Podcasts recommended to a user based on co-subscriptions
+    pub async fn suggestions(&self, user_id: i64, count: i64) -> AppResult<Vec<PodcastMetadata>> {
+        let podcasts = self
+            .podcast_repo
+            .suggestions_for_user(user_id, count)
+            .await?;
+        Ok(podcasts
+            .iter()
+            .map(|p| p.to_metadata(&self.config.base_url))
+            .collect())
+    }
+
+    /// Periodically recompute the co-subscription statistics behind
+    /// `suggestions`, starting immediately
+    pub async fn refresh_suggestions(self: Arc<Self>, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match self.podcast_repo.recompute_similarity().await {
+                Ok(pairs) => tracing::debug!("Recomputed {} podcast similarities", pairs),
+                Err(e) => tracing::error!("Recomputing podcast similarities failed: {:?}", e),
+            }
+        }
+    }
+
     /// Create minimal metadata when podcast not in database
     fn create_fallback_metadata(&self, url: &str) -> PodcastMetadata {
         PodcastMetadata {
@@ -74,6 +74,17 @@ impl SessionService {
         Ok(())
     }
 
+    pub async fn delete_user_sessions(&self, user_id: i64) -> AppResult<()> {
+        let count = self
+            .session_repo
+            .delete_by_user(user_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        tracing::info!("Deleted {} sessions of user {}", count, user_id);
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub async fn cleanup_expired_sessions(&self) -> AppResult<u64> {
         let current_time = SystemTime::now()
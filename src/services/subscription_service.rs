@@ -1,23 +1,56 @@
 use crate::{
+    config::Config,
     error::{AppError, AppResult},
-    models::SubscriptionChanges,
-    repository::SubscriptionRepository,
+    models::{RejectedSubscriptionChange, SubscriptionChanges, SubscriptionEvent},
+    repository::{DeviceSyncRepository, SubscriptionRepository},
 };
 
+/// Caps a client timestamp to at most `max_skew` seconds after `now`, so a
+/// device with a clock running ahead cannot win every later conflict
+fn bound_client_timestamp(timestamp: i64, now: i64, max_skew: i64) -> i64 {
+    timestamp.min(now.saturating_add(max_skew))
+}
+
 #[derive(Clone)]
 pub struct SubscriptionService {
     sub_repo: SubscriptionRepository,
+    device_sync_repo: DeviceSyncRepository,
+    config: Config,
 }
 
 impl SubscriptionService {
-    pub fn new(sub_repo: SubscriptionRepository) -> Self {
-        Self { sub_repo }
+    pub fn new(
+        sub_repo: SubscriptionRepository,
+        device_sync_repo: DeviceSyncRepository,
+        config: Config,
+    ) -> Self {
+        Self {
+            sub_repo,
+            device_sync_repo,
+            config,
+        }
+    }
+
+    /// Resolve a device to all devices sharing its subscriptions: the members
+    /// of its sync group, or just the device itself if it is not synchronized
+    async fn sync_group_devices(&self, device_id: i64) -> AppResult<Vec<i64>> {
+        match self.device_sync_repo.get_device_group(device_id).await? {
+            Some(group_id) => {
+                let mut devices = self.device_sync_repo.get_group_devices(group_id).await?;
+                if !devices.contains(&device_id) {
+                    devices.push(device_id);
+                }
+                Ok(devices)
+            }
+            None => Ok(vec![device_id]),
+        }
     }
 
     pub async fn get_subscriptions(&self, user_id: i64, device_id: i64) -> AppResult<Vec<String>> {
+        let devices = self.sync_group_devices(device_id).await?;
         let subs = self
             .sub_repo
-            .list_by_device(user_id, device_id)
+            .list_by_devices(user_id, &devices)
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
         Ok(subs)
@@ -38,8 +71,9 @@ impl SubscriptionService {
         device_id: i64,
         since: i64,
     ) -> AppResult<(Vec<String>, Vec<String>)> {
+        let devices = self.sync_group_devices(device_id).await?;
         self.sub_repo
-            .get_changes_since(user_id, device_id, since)
+            .get_changes_since(user_id, &devices, since)
             .await
             .map_err(|e| AppError::Internal(e.to_string()))
     }
@@ -50,30 +84,63 @@ impl SubscriptionService {
         device_id: i64,
         podcast_urls: Vec<String>,
     ) -> AppResult<()> {
-        self.sub_repo
-            .set_subscriptions(user_id, device_id, podcast_urls)
+        let devices = self.sync_group_devices(device_id).await?;
+        let rejected = self
+            .sub_repo
+            .set_subscriptions(user_id, &devices, podcast_urls)
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
+        if !rejected.is_empty() {
+            tracing::info!(
+                "{} subscription changes for device {} lost against newer changes",
+                rejected.len(),
+                device_id,
+            );
+        }
         Ok(())
     }
 
+    /// Apply changes made at `changes.timestamp` (client time). Returns the
+    /// changes that were rejected because a newer conflicting change exists.
     pub async fn upload_changes(
         &self,
         user_id: i64,
         device_id: i64,
-        changes: SubscriptionChanges,
-    ) -> AppResult<()> {
+        mut changes: SubscriptionChanges,
+    ) -> AppResult<Vec<RejectedSubscriptionChange>> {
         let count = changes.add.len() + changes.remove.len();
-        self.sub_repo
-            .apply_changes(user_id, device_id, changes)
+        changes.timestamp = bound_client_timestamp(
+            changes.timestamp,
+            chrono::Utc::now().timestamp(),
+            self.config.max_clock_skew,
+        );
+
+        let devices = self.sync_group_devices(device_id).await?;
+        let rejected = self
+            .sub_repo
+            .apply_changes(user_id, &devices, changes)
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
         tracing::info!(
-            "Uploaded {} subscription changes for device {}",
+            "Uploaded {} subscription changes for device {} ({} synchronized devices, {} rejected)",
             count,
             device_id,
+            devices.len(),
+            rejected.len(),
         );
-        Ok(())
+        Ok(rejected)
+    }
+
+    /// Every add and remove recorded for a device
+    pub async fn get_history(
+        &self,
+        user_id: i64,
+        device_id: i64,
+    ) -> AppResult<Vec<SubscriptionEvent>> {
+        self.sub_repo
+            .history(user_id, device_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))
     }
 
     pub async fn count_subscriptions(
@@ -87,3 +154,23 @@ impl SubscriptionService {
             .map_err(|e| AppError::Internal(e.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bound_client_timestamp_keeps_past_and_small_skew() {
+        assert_eq!(bound_client_timestamp(1_000, 5_000, 300), 1_000);
+        assert_eq!(bound_client_timestamp(5_200, 5_000, 300), 5_200);
+    }
+
+    #[test]
+    fn test_bound_client_timestamp_caps_future() {
+        assert_eq!(bound_client_timestamp(9_000, 5_000, 300), 5_300);
+        assert_eq!(
+            bound_client_timestamp(i64::MAX, i64::MAX - 1, 300),
+            i64::MAX
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::error::{AppError, AppResult};
+use crate::models::PodcastAlias;
+use crate::repository::UrlRepository;
+
+/// Canonical form of podcast and episode URLs
+#[derive(Clone)]
+pub struct UrlService {
+    url_repo: UrlRepository,
+    tracking_params: Vec<String>,
+}
+
+impl UrlService {
+    pub fn new(url_repo: UrlRepository, tracking_params: Vec<String>) -> Self {
+        Self {
+            url_repo,
+            tracking_params,
+        }
+    }
+
+    /// Normalized URL, empty for invalid URLs
+    pub fn sanitize_url(&self, url: &str) -> String {
+        crate::utils::sanitize_url(url, &self.tracking_params)
+    }
+
+    /// Normalized podcast URL, following the alias of a moved podcast
+    pub async fn sanitize_podcast_url(&self, url: &str) -> AppResult<String> {
+        let url = self.sanitize_url(url);
+        Ok(self.resolve_alias(url).await?)
+    }
+
+    /// Normalized podcast URLs and the `[original, normalized]` pairs of the
+    /// ones that changed, as reported to clients in `update_urls`. URLs of
+    /// moved podcasts are replaced by their current URL.
+    pub async fn sanitize_urls(
+        &self,
+        urls: &[String],
+    ) -> AppResult<(Vec<String>, Vec<[String; 2]>)> {
+        let (sanitized, _) = crate::utils::sanitize_urls(urls, &self.tracking_params);
+
+        let mut resolved = Vec::with_capacity(sanitized.len());
+        for url in sanitized {
+            resolved.push(self.resolve_alias(url).await?);
+        }
+
+        let updates = urls
+            .iter()
+            .zip(&resolved)
+            .filter(|(original, url)| original != url)
+            .map(|(original, url)| [original.clone(), url.clone()])
+            .collect();
+
+        Ok((resolved, updates))
+    }
+
+    async fn resolve_alias(&self, url: String) -> Result<String, sqlx::Error> {
+        if url.is_empty() {
+            return Ok(url);
+        }
+        Ok(self.url_repo.resolve_alias(&url).await?.unwrap_or(url))
+    }
+
+    /// Records that the podcast at `old` moved to `new` and moves everything
+    /// stored under `old` there. Subscribed clients see the move as a
+    /// removal of `old` and an addition of `new`. `source` is one of
+    /// `redirect`, `new-feed-url` or `admin`. Returns false if both URLs
+    /// already denote the same podcast.
+    pub async fn move_podcast(&self, old: &str, new: &str, source: &str) -> AppResult<bool> {
+        let old = self.sanitize_url(old);
+        let new = self.sanitize_podcast_url(new).await?;
+        if old.is_empty() || new.is_empty() {
+            return Err(AppError::BadRequest("Invalid podcast URL".to_string()));
+        }
+        if old == new {
+            return Ok(false);
+        }
+
+        self.url_repo.add_alias(&old, &new, source).await?;
+        self.url_repo.rewrite_podcast_url(&old, &new).await?;
+        Ok(true)
+    }
+
+    pub async fn list_aliases(&self) -> AppResult<Vec<PodcastAlias>> {
+        Ok(self.url_repo.aliases().await?)
+    }
+
+    /// Stops mapping `alias`; data already moved stays at the new URL
+    pub async fn delete_alias(&self, alias: &str) -> AppResult<()> {
+        if !self.url_repo.delete_alias(alias).await? {
+            return Err(AppError::NotFound(format!("Alias '{}' not found", alias)));
+        }
+        Ok(())
+    }
+
+    /// Rewrites stored URLs that are not in canonical form or belong to a
+    /// moved podcast, merging the duplicates this creates. Runs once as a
+    /// migration; after a change of the configured tracking parameters an
+    /// admin runs it again. Returns the number of rewritten URLs.
+    pub async fn normalize_stored_urls(&self) -> AppResult<usize> {
+        let mut rewritten = 0;
+
+        for url in self.url_repo.podcast_urls().await? {
+            let normalized = self.sanitize_podcast_url(&url).await?;
+            if !normalized.is_empty() && normalized != url {
+                self.url_repo.rewrite_podcast_url(&url, &normalized).await?;
+                rewritten += 1;
+            }
+        }
+
+        for url in self.url_repo.episode_urls().await? {
+            let normalized = self.sanitize_url(&url);
+            if !normalized.is_empty() && normalized != url {
+                self.url_repo.rewrite_episode_url(&url, &normalized).await?;
+                rewritten += 1;
+            }
+        }
+
+        Ok(rewritten)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{
+        test_db, ChangeLogRepository, DeviceRepository, SubscriptionRepository, UserRepository,
+    };
+
+    #[tokio::test]
+    async fn test_moved_podcast_is_synced_as_remove_and_add() {
+        let (pool, _dir) = test_db().await;
+        let user = UserRepository::new(pool.clone())
+            .create("alice", "hash", false)
+            .await
+            .unwrap();
+        let device = DeviceRepository::new(pool.clone())
+            .create(user.id, "phone", None, None)
+            .await
+            .unwrap();
+        let subscriptions = SubscriptionRepository::new(pool.clone());
+        subscriptions
+            .set_subscriptions(user.id, &[device], vec!["http://old.example/feed".into()])
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO settings (user_id, scope, podcast_url, key, value)
+            VALUES (?, 'podcast', 'http://old.example/feed', 'speed', '1.5')
+            "#,
+        )
+        .bind(user.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let since = ChangeLogRepository::new(pool.clone())
+            .current_cursor()
+            .await
+            .unwrap();
+
+        let service = UrlService::new(UrlRepository::new(pool.clone()), Vec::new());
+        let moved = service
+            .move_podcast(
+                "http://old.example/feed",
+                "http://new.example/feed",
+                "admin",
+            )
+            .await
+            .unwrap();
+        assert!(moved);
+
+        let (added, removed) = subscriptions
+            .get_changes_since(user.id, &[device], since)
+            .await
+            .unwrap();
+        assert_eq!(added, vec!["http://new.example/feed"]);
+        assert_eq!(removed, vec!["http://old.example/feed"]);
+
+        let setting_url: String = sqlx::query_scalar("SELECT podcast_url FROM settings")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(setting_url, "http://new.example/feed");
+    }
+}
@@ -1,17 +1,21 @@
 use crate::{
     error::{AppError, AppResult},
-    repository::UserRepository,
+    repository::{SessionRepository, UserRepository},
 };
 use argon2::PasswordVerifier;
 
 #[derive(Clone)]
 pub struct UserService {
     user_repo: UserRepository,
+    session_repo: SessionRepository,
 }
 
 impl UserService {
-    pub fn new(user_repo: UserRepository) -> Self {
-        Self { user_repo }
+    pub fn new(user_repo: UserRepository, session_repo: SessionRepository) -> Self {
+        Self {
+            user_repo,
+            session_repo,
+        }
     }
 
     pub async fn create_user(
@@ -19,12 +23,53 @@ impl UserService {
         username: &str,
         password_hash: &str,
         is_admin: bool,
-    ) -> AppResult<()> {
+    ) -> AppResult<crate::models::User> {
         self.user_repo
             .create(username, password_hash, is_admin)
             .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
-        Ok(())
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    /// Validate and create a new user account from a plain-text password
+    pub async fn register_user(
+        &self,
+        username: &str,
+        password: &str,
+        is_admin: bool,
+    ) -> AppResult<crate::models::User> {
+        Self::validate_username(username)?;
+        if password.is_empty() {
+            return Err(AppError::BadRequest("Password cannot be empty".to_string()));
+        }
+
+        if self.find_by_username(username).await?.is_some() {
+            return Err(AppError::BadRequest(format!(
+                "User '{}' already exists",
+                username
+            )));
+        }
+
+        let password_hash = Self::hash_password(password)?;
+        let user = self.create_user(username, &password_hash, is_admin).await?;
+        tracing::info!("Created user: {} (admin: {})", username, is_admin);
+        Ok(user)
+    }
+
+    /// Usernames appear in API paths, so only URL-safe characters are allowed
+    fn validate_username(username: &str) -> AppResult<()> {
+        let valid = !username.is_empty()
+            && username
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '@'));
+
+        if valid {
+            Ok(())
+        } else {
+            Err(AppError::BadRequest(format!(
+                "Invalid username '{}': only letters, digits, '_', '-', '.' and '@' are allowed",
+                username
+            )))
+        }
     }
 
     pub async fn find_by_id(&self, id: i64) -> AppResult<Option<crate::models::User>> {
@@ -34,7 +79,111 @@ impl UserService {
             .map_err(|e| AppError::Internal(e.to_string()))
     }
 
-    pub async fn verify_credentials(&self, username: &str, password: &str) -> AppResult<i64> {
+    pub async fn find_by_username(&self, username: &str) -> AppResult<Option<crate::models::User>> {
+        self.user_repo
+            .find_by_username(username)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    pub async fn list_users(&self) -> AppResult<Vec<crate::models::User>> {
+        self.user_repo
+            .list_all()
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))
+    }
+
+    /// Hash and store a new password for a user
+    async fn set_password(&self, id: i64, password: &str) -> AppResult<()> {
+        if password.is_empty() {
+            return Err(AppError::BadRequest("Password cannot be empty".to_string()));
+        }
+
+        let password_hash = Self::hash_password(password)?;
+        let updated = self
+            .user_repo
+            .update_password(id, &password_hash)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if !updated {
+            return Err(AppError::NotFound(format!("User {} not found", id)));
+        }
+
+        tracing::info!("Password changed for user {}", id);
+        Ok(())
+    }
+
+    pub async fn set_disabled(&self, id: i64, disabled: bool) -> AppResult<()> {
+        let updated = self
+            .user_repo
+            .set_disabled(id, disabled)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if !updated {
+            return Err(AppError::NotFound(format!("User {} not found", id)));
+        }
+
+        tracing::info!("User {} disabled: {}", id, disabled);
+        Ok(())
+    }
+
+    /// Set a new password on behalf of an admin; existing logins of the user
+    /// must not survive the reset
+    pub async fn reset_password(&self, id: i64, password: &str) -> AppResult<()> {
+        self.set_password(id, password).await?;
+        self.session_repo
+            .delete_by_user(id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Change a user's own password after verifying the current one
+    pub async fn change_password(
+        &self,
+        id: i64,
+        current_password: &str,
+        new_password: &str,
+    ) -> AppResult<()> {
+        self.verify_user_password(id, current_password).await?;
+        self.set_password(id, new_password).await
+    }
+
+    /// Erase a user's own account after verifying their password
+    pub async fn delete_account(&self, id: i64, password: &str) -> AppResult<()> {
+        self.verify_user_password(id, password).await?;
+        self.delete_user(id).await
+    }
+
+    async fn verify_user_password(&self, id: i64, password: &str) -> AppResult<()> {
+        let user = self.find_by_id(id).await?.ok_or(AppError::Authentication)?;
+        self.verify_password(&user.password_hash, password)
+    }
+
+    /// Delete a user together with all of their data
+    pub async fn delete_user(&self, id: i64) -> AppResult<()> {
+        let deleted = self
+            .user_repo
+            .delete(id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if !deleted {
+            return Err(AppError::NotFound(format!("User {} not found", id)));
+        }
+
+        tracing::info!("Deleted user {}", id);
+        Ok(())
+    }
+
+    /// Verify a username/password pair. Disabled accounts never authenticate.
+    pub async fn verify_credentials(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> AppResult<crate::models::User> {
         let user = self
             .user_repo
             .find_by_username(username)
@@ -44,7 +193,12 @@ impl UserService {
 
         self.verify_password(&user.password_hash, password)?;
 
-        Ok(user.id)
+        if user.disabled {
+            tracing::warn!("Login attempt for disabled user: {}", username);
+            return Err(AppError::Authentication);
+        }
+
+        Ok(user)
     }
 
     pub async fn is_empty(&self) -> AppResult<bool> {
@@ -3,8 +3,9 @@ use std::sync::Arc;
 
 use crate::config::Config;
 use crate::services::{
-    DeviceService, DeviceSyncService, EpisodeActionService, FavoriteService, PodcastService,
-    SessionService, SettingService, SubscriptionService, UserService,
+    AdminSessionService, BookmarkService, ChangeLogService, ChapterService, DeviceService,
+    DeviceSyncService, EpisodeActionService, EpisodeService, FavoriteService, PodcastListService,
+    PodcastService, SessionService, SettingService, SubscriptionService, UrlService, UserService,
 };
 
 #[derive(Clone)]
@@ -14,10 +15,17 @@ pub struct AppState {
     pub device_sync_service: Arc<DeviceSyncService>,
     pub subscription_service: Arc<SubscriptionService>,
     pub episode_action_service: Arc<EpisodeActionService>,
+    pub episode_service: Arc<EpisodeService>,
     pub setting_service: Arc<SettingService>,
     pub session_service: Arc<SessionService>,
     pub favorite_service: Arc<FavoriteService>,
+    pub bookmark_service: Arc<BookmarkService>,
     pub podcast_service: Arc<PodcastService>,
+    pub podcast_list_service: Arc<PodcastListService>,
+    pub admin_session_service: Arc<AdminSessionService>,
+    pub change_log_service: Arc<ChangeLogService>,
+    pub chapter_service: Arc<ChapterService>,
+    pub url_service: Arc<UrlService>,
 }
 
 impl AppState {
@@ -30,17 +38,45 @@ impl AppState {
         let setting_repo = crate::repository::SettingRepository::new(pool.clone());
         let session_repo = crate::repository::SessionRepository::new(pool.clone());
         let favorite_repo = crate::repository::FavoriteRepository::new(pool.clone());
+        let bookmark_repo = crate::repository::BookmarkRepository::new(pool.clone());
         let podcast_repo = crate::repository::PodcastRepository::new(pool.clone());
+        let podcast_list_repo = crate::repository::PodcastListRepository::new(pool.clone());
+        let episode_repo = crate::repository::EpisodeRepository::new(pool.clone());
+        let admin_session_repo = crate::repository::AdminSessionRepository::new(pool.clone());
+        let change_log_repo = crate::repository::ChangeLogRepository::new(pool.clone());
+        let url_repo = crate::repository::UrlRepository::new(pool.clone());
+        let chapter_repo = crate::repository::ChapterRepository::new(pool.clone());
 
-        let user_service = Arc::new(UserService::new(user_repo));
+        let user_service = Arc::new(UserService::new(user_repo.clone(), session_repo.clone()));
         let device_service = Arc::new(DeviceService::new(device_repo.clone()));
-        let device_sync_service = Arc::new(DeviceSyncService::new(device_sync_repo, device_repo));
-        let subscription_service = Arc::new(SubscriptionService::new(sub_repo));
-        let episode_action_service = Arc::new(EpisodeActionService::new(action_repo));
+        let device_sync_service = Arc::new(DeviceSyncService::new(
+            device_sync_repo.clone(),
+            device_repo,
+            sub_repo.clone(),
+        ));
+        let subscription_service = Arc::new(SubscriptionService::new(
+            sub_repo,
+            device_sync_repo,
+            config.clone(),
+        ));
+        let episode_action_service = Arc::new(EpisodeActionService::new(action_repo.clone()));
+        let episode_service = Arc::new(EpisodeService::new(
+            episode_repo.clone(),
+            action_repo,
+            config.clone(),
+        ));
         let setting_service = Arc::new(SettingService::new(setting_repo));
         let session_service = Arc::new(SessionService::new(session_repo));
-        let favorite_service = Arc::new(FavoriteService::new(favorite_repo));
+        let favorite_service = Arc::new(FavoriteService::new(favorite_repo, episode_repo));
+        let bookmark_service = Arc::new(BookmarkService::new(bookmark_repo));
+        let url_service = Arc::new(UrlService::new(url_repo, config.tracking_params.clone()));
+        let podcast_list_service =
+            Arc::new(PodcastListService::new(podcast_list_repo, config.clone()));
         let podcast_service = Arc::new(PodcastService::new(Arc::new(podcast_repo), config));
+        let admin_session_service =
+            Arc::new(AdminSessionService::new(admin_session_repo, user_repo));
+        let change_log_service = Arc::new(ChangeLogService::new(change_log_repo));
+        let chapter_service = Arc::new(ChapterService::new(chapter_repo));
 
         Self {
             user_service,
@@ -48,10 +84,17 @@ impl AppState {
             device_sync_service,
             subscription_service,
             episode_action_service,
+            episode_service,
             setting_service,
             session_service,
             favorite_service,
+            bookmark_service,
             podcast_service,
+            podcast_list_service,
+            admin_session_service,
+            change_log_service,
+            chapter_service,
+            url_service,
         }
     }
 }
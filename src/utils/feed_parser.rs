@@ -0,0 +1,531 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Channel-level metadata and episodes of an RSS 2.0, RSS 1.0 or Atom feed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParsedFeed {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub logo_url: Option<String>,
+    /// Lower-cased channel categories, used as directory tags
+    pub categories: Vec<String>,
+    /// URL the publisher moved the feed to (`<itunes:new-feed-url>`)
+    pub new_feed_url: Option<String>,
+    pub episodes: Vec<ParsedEpisode>,
+}
+
+/// A feed item with a media enclosure
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedEpisode {
+    /// Feed-provided identifier, the media URL if the feed has none
+    pub guid: String,
+    /// Media (enclosure) URL
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    /// Duration in seconds
+    pub duration: Option<i64>,
+    /// Release date as unix timestamp
+    pub released: Option<i64>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FeedParseError {
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] quick_xml::Error),
+
+    #[error("Document is not an RSS or Atom feed")]
+    NotAFeed,
+}
+
+/// Elements whose text content we are interested in
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    Description,
+    Summary,
+    Website,
+    Image,
+    Icon,
+    Category,
+    NewFeedUrl,
+    EpisodeGuid,
+    EpisodeTitle,
+    EpisodeDescription,
+    EpisodeSummary,
+    EpisodeWebsite,
+    EpisodeDuration,
+    EpisodeReleased,
+    EpisodeUpdated,
+}
+
+fn as_strs(path: &[String]) -> Vec<&str> {
+    path.iter().map(String::as_str).collect()
+}
+
+fn is_episode(path: &[String]) -> bool {
+    matches!(
+        as_strs(path).as_slice(),
+        ["rss", "channel", "item"] | ["rdf:RDF", "item"] | ["feed", "entry"]
+    )
+}
+
+fn text_field(path: &[String]) -> Option<Field> {
+    match as_strs(path).as_slice() {
+        ["rss" | "rdf:RDF", "channel", "title"] | ["feed", "title"] => Some(Field::Title),
+        ["rss" | "rdf:RDF", "channel", "description"] | ["feed", "subtitle"] => {
+            Some(Field::Description)
+        }
+        ["rss", "channel", "itunes:summary"] => Some(Field::Summary),
+        ["rss" | "rdf:RDF", "channel", "link"] => Some(Field::Website),
+        ["rss", "channel", "image", "url"] | ["rdf:RDF", "image", "url"] | ["feed", "logo"] => {
+            Some(Field::Image)
+        }
+        ["feed", "icon"] => Some(Field::Icon),
+        ["rss" | "rdf:RDF", "channel", "category"] => Some(Field::Category),
+        ["rss", "channel", "itunes:new-feed-url"] => Some(Field::NewFeedUrl),
+        ["rss", "channel", "item", child] | ["rdf:RDF", "item", child] => match *child {
+            "guid" => Some(Field::EpisodeGuid),
+            "title" => Some(Field::EpisodeTitle),
+            "description" => Some(Field::EpisodeDescription),
+            "itunes:summary" => Some(Field::EpisodeSummary),
+            "link" => Some(Field::EpisodeWebsite),
+            "itunes:duration" => Some(Field::EpisodeDuration),
+            "pubDate" | "dc:date" => Some(Field::EpisodeReleased),
+            _ => None,
+        },
+        ["feed", "entry", child] => match *child {
+            "id" => Some(Field::EpisodeGuid),
+            "title" => Some(Field::EpisodeTitle),
+            "summary" => Some(Field::EpisodeDescription),
+            "content" => Some(Field::EpisodeSummary),
+            "published" => Some(Field::EpisodeReleased),
+            "updated" => Some(Field::EpisodeUpdated),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == name)
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Parse `HH:MM:SS`, `MM:SS` or plain (possibly fractional) seconds
+fn parse_duration(value: &str) -> Option<i64> {
+    value.split(':').try_fold(0i64, |total, part| {
+        let seconds = part.trim().parse::<f64>().ok()?;
+        Some(total * 60 + seconds as i64)
+    })
+}
+
+/// Parse RFC 2822 (RSS) and RFC 3339 (Atom) dates into a unix timestamp
+fn parse_date(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc2822(value)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(value))
+        .map(|date| date.timestamp())
+        .ok()
+}
+
+fn set_once(slot: &mut Option<String>, value: String) {
+    if slot.is_none() {
+        *slot = Some(value);
+    }
+}
+
+#[derive(Default)]
+struct EpisodeBuilder {
+    guid: Option<String>,
+    url: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    summary: Option<String>,
+    website: Option<String>,
+    duration: Option<String>,
+    released: Option<String>,
+    updated: Option<String>,
+}
+
+impl EpisodeBuilder {
+    /// Items without a media enclosure are not episodes
+    fn build(self) -> Option<ParsedEpisode> {
+        let url = self.url?;
+        Some(ParsedEpisode {
+            guid: self.guid.unwrap_or_else(|| url.clone()),
+            title: self.title,
+            description: self.description.or(self.summary),
+            website: self.website,
+            duration: self.duration.as_deref().and_then(parse_duration),
+            released: self
+                .released
+                .or(self.updated)
+                .as_deref()
+                .and_then(parse_date),
+            url,
+        })
+    }
+}
+
+#[derive(Default)]
+struct FeedBuilder {
+    feed: ParsedFeed,
+    summary: Option<String>,
+    itunes_image: Option<String>,
+    icon: Option<String>,
+    episode: Option<EpisodeBuilder>,
+}
+
+impl FeedBuilder {
+    /// Elements carrying their value in attributes instead of text
+    fn element(&mut self, path: &[String], element: &BytesStart) {
+        match as_strs(path).as_slice() {
+            ["rss", "channel", "itunes:category"]
+            | ["rss", "channel", "itunes:category", "itunes:category"] => {
+                if let Some(category) = attribute(element, b"text") {
+                    self.add_category(&category);
+                }
+            }
+            ["feed", "category"] => {
+                if let Some(category) = attribute(element, b"term") {
+                    self.add_category(&category);
+                }
+            }
+            ["rss", "channel", "itunes:image"] => {
+                self.itunes_image = self.itunes_image.take().or(attribute(element, b"href"));
+            }
+            ["feed", "link"] => {
+                let rel = attribute(element, b"rel");
+                if matches!(rel.as_deref(), None | Some("alternate")) && self.feed.website.is_none()
+                {
+                    self.feed.website = attribute(element, b"href");
+                }
+            }
+            ["rss", "channel", "item", "enclosure"] => {
+                if let (Some(episode), Some(url)) =
+                    (self.episode.as_mut(), attribute(element, b"url"))
+                {
+                    set_once(&mut episode.url, url);
+                }
+            }
+            ["feed", "entry", "link"] => {
+                let Some(episode) = self.episode.as_mut() else {
+                    return;
+                };
+                let Some(href) = attribute(element, b"href") else {
+                    return;
+                };
+                match attribute(element, b"rel").as_deref() {
+                    Some("enclosure") => set_once(&mut episode.url, href),
+                    None | Some("alternate") => set_once(&mut episode.website, href),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, field: Field, text: String) {
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+
+        if field == Field::Category {
+            self.add_category(&text);
+            return;
+        }
+
+        let slot = match (field, self.episode.as_mut()) {
+            (Field::Title, _) => &mut self.feed.title,
+            (Field::Description, _) => &mut self.feed.description,
+            (Field::Summary, _) => &mut self.summary,
+            (Field::Website, _) => &mut self.feed.website,
+            (Field::Image, _) => &mut self.feed.logo_url,
+            (Field::Icon, _) => &mut self.icon,
+            (Field::NewFeedUrl, _) => &mut self.feed.new_feed_url,
+            (Field::Category, _) => return,
+            (_, None) => return,
+            (Field::EpisodeGuid, Some(episode)) => &mut episode.guid,
+            (Field::EpisodeTitle, Some(episode)) => &mut episode.title,
+            (Field::EpisodeDescription, Some(episode)) => &mut episode.description,
+            (Field::EpisodeSummary, Some(episode)) => &mut episode.summary,
+            (Field::EpisodeWebsite, Some(episode)) => &mut episode.website,
+            (Field::EpisodeDuration, Some(episode)) => &mut episode.duration,
+            (Field::EpisodeReleased, Some(episode)) => &mut episode.released,
+            (Field::EpisodeUpdated, Some(episode)) => &mut episode.updated,
+        };
+        set_once(slot, text);
+    }
+
+    fn add_category(&mut self, category: &str) {
+        let category = category.trim().to_lowercase();
+        if !category.is_empty() && !self.feed.categories.contains(&category) {
+            self.feed.categories.push(category);
+        }
+    }
+
+    fn start_episode(&mut self) {
+        self.episode = Some(EpisodeBuilder::default());
+    }
+
+    fn finish_episode(&mut self) {
+        if let Some(episode) = self.episode.take().and_then(EpisodeBuilder::build) {
+            self.feed.episodes.push(episode);
+        }
+    }
+
+    fn build(self) -> ParsedFeed {
+        ParsedFeed {
+            description: self.feed.description.or(self.summary),
+            // iTunes artwork is usually the high resolution one
+            logo_url: self.itunes_image.or(self.feed.logo_url).or(self.icon),
+            ..self.feed
+        }
+    }
+}
+
+/// Parse the channel metadata and episodes out of a podcast feed document
+pub fn parse_feed(xml: &[u8]) -> Result<ParsedFeed, FeedParseError> {
+    let mut reader = Reader::from_reader(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut builder = FeedBuilder::default();
+    let mut path: Vec<String> = Vec::new();
+    // Text of the currently open field, which may arrive in several chunks
+    let mut current: Option<(Field, String)> = None;
+    let mut is_feed = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+                if path.is_empty() {
+                    is_feed = matches!(name.as_str(), "rss" | "rdf:RDF" | "feed");
+                    if !is_feed {
+                        return Err(FeedParseError::NotAFeed);
+                    }
+                }
+                path.push(name);
+                if is_episode(&path) {
+                    builder.start_episode();
+                }
+                builder.element(&path, &element);
+                current = text_field(&path).map(|field| (field, String::new()));
+            }
+            Event::Empty(element) => {
+                let name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+                path.push(name);
+                builder.element(&path, &element);
+                path.pop();
+            }
+            Event::Text(text) => {
+                if let Some((_, value)) = current.as_mut() {
+                    value.push_str(&text.unescape()?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some((_, value)) = current.as_mut() {
+                    value.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::End(_) => {
+                if let Some((field, value)) = current.take() {
+                    builder.text(field, value);
+                }
+                if is_episode(&path) {
+                    builder.finish_episode();
+                }
+                path.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !is_feed {
+        return Err(FeedParseError::NotAFeed);
+    }
+
+    Ok(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss_channel() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+              <channel>
+                <title>My Podcast &amp; Friends</title>
+                <link>https://example.com/</link>
+                <description><![CDATA[A <b>great</b> show]]></description>
+                <itunes:image href="https://example.com/large.jpg"/>
+                <itunes:category text="Technology">
+                  <itunes:category text="Podcasting"/>
+                </itunes:category>
+                <category>technology</category>
+                <image><url>https://example.com/small.jpg</url><title>Logo</title></image>
+                <item><title>Episode 1</title><link>https://example.com/1</link></item>
+              </channel>
+            </rss>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("My Podcast & Friends"));
+        assert_eq!(feed.description.as_deref(), Some("A <b>great</b> show"));
+        assert_eq!(feed.website.as_deref(), Some("https://example.com/"));
+        assert_eq!(
+            feed.logo_url.as_deref(),
+            Some("https://example.com/large.jpg")
+        );
+        assert_eq!(feed.categories, vec!["technology", "podcasting"]);
+    }
+
+    #[test]
+    fn test_parse_rss_falls_back_to_itunes_summary_and_image() {
+        let xml = br#"<rss><channel>
+                <title>Show</title>
+                <itunes:summary>Summary text</itunes:summary>
+                <image><url>https://example.com/small.jpg</url></image>
+            </channel></rss>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.description.as_deref(), Some("Summary text"));
+        assert_eq!(
+            feed.logo_url.as_deref(),
+            Some("https://example.com/small.jpg")
+        );
+    }
+
+    #[test]
+    fn test_parse_new_feed_url() {
+        let xml = br#"<rss><channel>
+                <title>Show</title>
+                <itunes:new-feed-url>
+                    https://new.example.com/feed.xml
+                </itunes:new-feed-url>
+            </channel></rss>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(
+            feed.new_feed_url.as_deref(),
+            Some("https://new.example.com/feed.xml")
+        );
+    }
+
+    #[test]
+    fn test_parse_atom_feed() {
+        let xml = br#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Atom Show</title>
+              <subtitle>Atom description</subtitle>
+              <link rel="self" href="https://example.com/feed.atom"/>
+              <link rel="alternate" href="https://example.com/"/>
+              <icon>https://example.com/icon.png</icon>
+              <entry><title>Entry</title></entry>
+            </feed>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Atom Show"));
+        assert_eq!(feed.description.as_deref(), Some("Atom description"));
+        assert_eq!(feed.website.as_deref(), Some("https://example.com/"));
+        assert_eq!(
+            feed.logo_url.as_deref(),
+            Some("https://example.com/icon.png")
+        );
+    }
+
+    #[test]
+    fn test_parse_rss_episodes() {
+        let xml = br#"<rss><channel>
+                <title>Show</title>
+                <item>
+                  <title>Episode 2</title>
+                  <guid isPermaLink="false">ep-2</guid>
+                  <link>https://example.com/2</link>
+                  <description>Second</description>
+                  <pubDate>Tue, 01 Oct 2024 10:00:00 GMT</pubDate>
+                  <itunes:duration>1:02:03</itunes:duration>
+                  <enclosure url="https://cdn.example.com/2.mp3" type="audio/mpeg" length="1"/>
+                </item>
+                <item>
+                  <title>Episode 1</title>
+                  <itunes:duration>95</itunes:duration>
+                  <enclosure url="https://cdn.example.com/1.mp3" type="audio/mpeg" length="1"/>
+                </item>
+                <item><title>Text only post</title></item>
+            </channel></rss>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Show"));
+        assert_eq!(feed.episodes.len(), 2);
+
+        let episode = &feed.episodes[0];
+        assert_eq!(episode.guid, "ep-2");
+        assert_eq!(episode.url, "https://cdn.example.com/2.mp3");
+        assert_eq!(episode.title.as_deref(), Some("Episode 2"));
+        assert_eq!(episode.description.as_deref(), Some("Second"));
+        assert_eq!(episode.website.as_deref(), Some("https://example.com/2"));
+        assert_eq!(episode.duration, Some(3723));
+        assert_eq!(episode.released, Some(1727776800));
+
+        // Items without a guid are identified by their media URL
+        assert_eq!(feed.episodes[1].guid, "https://cdn.example.com/1.mp3");
+        assert_eq!(feed.episodes[1].duration, Some(95));
+        assert_eq!(feed.episodes[1].released, None);
+    }
+
+    #[test]
+    fn test_parse_atom_entries() {
+        let xml = br#"<feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Atom Show</title>
+              <entry>
+                <id>urn:uuid:1</id>
+                <title>Entry</title>
+                <updated>2024-10-02T10:00:00Z</updated>
+                <published>2024-10-01T10:00:00Z</published>
+                <link rel="alternate" href="https://example.com/entry"/>
+                <link rel="enclosure" href="https://cdn.example.com/entry.mp3"/>
+              </entry>
+            </feed>"#;
+
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Atom Show"));
+        assert_eq!(feed.episodes.len(), 1);
+
+        let episode = &feed.episodes[0];
+        assert_eq!(episode.guid, "urn:uuid:1");
+        assert_eq!(episode.url, "https://cdn.example.com/entry.mp3");
+        assert_eq!(
+            episode.website.as_deref(),
+            Some("https://example.com/entry")
+        );
+        assert_eq!(episode.released, Some(1727776800));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("3600"), Some(3600));
+        assert_eq!(parse_duration("12:34"), Some(754));
+        assert_eq!(parse_duration("01:00:01"), Some(3601));
+        assert_eq!(parse_duration("95.7"), Some(95));
+        assert_eq!(parse_duration("unknown"), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_html() {
+        let html = b"<html><head><title>Not a feed</title></head></html>";
+        assert!(matches!(parse_feed(html), Err(FeedParseError::NotAFeed)));
+    }
+}
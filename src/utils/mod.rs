@@ -1,3 +1,11 @@
+pub mod feed_parser;
+pub mod opml;
+pub mod search_query;
+pub mod timestamp;
 pub mod url_sanitizer;
 
+pub use feed_parser::{parse_feed, ParsedFeed};
+pub use opml::{parse_opml, write_opml, OpmlOutline};
+pub use search_query::fts_query;
+pub use timestamp::{deserialize_timestamp, parse_timestamp};
 pub use url_sanitizer::{sanitize_url, sanitize_urls};
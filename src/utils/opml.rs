@@ -0,0 +1,207 @@
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+/// A feed listed in an OPML document
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OpmlOutline {
+    /// Feed URL (`xmlUrl`)
+    pub url: String,
+    pub title: Option<String>,
+    /// Website of the podcast (`htmlUrl`)
+    pub website: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OpmlParseError {
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] quick_xml::Error),
+
+    #[error("Document is not an OPML document")]
+    NotOpml,
+}
+
+/// Attribute value by case-insensitive name; clients disagree on `xmlUrl`
+/// vs `xmlurl`
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref().eq_ignore_ascii_case(name.as_bytes()))
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Outlines with a feed URL; the ones without are folders and only
+/// contribute the feeds nested inside them
+fn outline(element: &BytesStart) -> Option<OpmlOutline> {
+    Some(OpmlOutline {
+        url: attribute(element, "xmlUrl")?,
+        title: attribute(element, "title").or_else(|| attribute(element, "text")),
+        website: attribute(element, "htmlUrl"),
+    })
+}
+
+/// Feeds of an OPML document, in document order and including those in
+/// nested folders
+pub fn parse_opml(xml: &[u8]) -> Result<Vec<OpmlOutline>, OpmlParseError> {
+    let mut reader = Reader::from_reader(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut outlines = Vec::new();
+    let mut is_opml = false;
+    let mut depth = 0usize;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) => {
+                if depth == 0 {
+                    is_opml = element.name().as_ref() == b"opml";
+                }
+                depth += 1;
+                if is_opml && element.name().as_ref() == b"outline" {
+                    outlines.extend(outline(&element));
+                }
+            }
+            Event::Empty(element) => {
+                if depth == 0 {
+                    is_opml = element.name().as_ref() == b"opml";
+                }
+                if is_opml && element.name().as_ref() == b"outline" {
+                    outlines.extend(outline(&element));
+                }
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !is_opml {
+        return Err(OpmlParseError::NotOpml);
+    }
+
+    Ok(outlines)
+}
+
+fn write_document(
+    writer: &mut Writer<Vec<u8>>,
+    title: &str,
+    outlines: &[OpmlOutline],
+) -> std::io::Result<()> {
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+    let mut opml = BytesStart::new("opml");
+    opml.push_attribute(("version", "2.0"));
+    writer.write_event(Event::Start(opml))?;
+
+    writer.write_event(Event::Start(BytesStart::new("head")))?;
+    writer.write_event(Event::Start(BytesStart::new("title")))?;
+    writer.write_event(Event::Text(BytesText::new(title)))?;
+    writer.write_event(Event::End(BytesEnd::new("title")))?;
+    writer.write_event(Event::End(BytesEnd::new("head")))?;
+
+    writer.write_event(Event::Start(BytesStart::new("body")))?;
+    for outline in outlines {
+        let title = outline.title.as_deref().unwrap_or(&outline.url);
+        let mut element = BytesStart::new("outline");
+        element.push_attribute(("type", "rss"));
+        element.push_attribute(("text", title));
+        element.push_attribute(("title", title));
+        element.push_attribute(("xmlUrl", outline.url.as_str()));
+        if let Some(website) = &outline.website {
+            element.push_attribute(("htmlUrl", website.as_str()));
+        }
+        writer.write_event(Event::Empty(element))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new("body")))?;
+
+    writer.write_event(Event::End(BytesEnd::new("opml")))?;
+    Ok(())
+}
+
+/// OPML 2.0 document listing `outlines` as feeds
+pub fn write_opml(title: &str, outlines: &[OpmlOutline]) -> String {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    write_document(&mut writer, title, outlines).expect("writing to memory cannot fail");
+    String::from_utf8(writer.into_inner()).expect("OPML is written as UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(outlines: &[OpmlOutline]) -> Vec<&str> {
+        outlines
+            .iter()
+            .map(|outline| outline.url.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_nested_and_multiline_outlines() {
+        let xml = br#"<?xml version="1.0"?>
+            <opml version="1.1">
+              <head><title>Export</title></head>
+              <body>
+                <outline text="News">
+                  <outline type="rss" text="Daily"
+                           xmlUrl="https://example.com/daily.xml?a=1&amp;b=2"/>
+                  <outline text="Tech">
+                    <outline type='rss' title='Deep' xmlUrl='https://example.com/deep.xml'></outline>
+                  </outline>
+                </outline>
+                <outline type="rss" text="Loud" xmlurl="https://example.com/loud.xml"
+                         htmlUrl="https://example.com/"/>
+              </body>
+            </opml>"#;
+
+        let outlines = parse_opml(xml).unwrap();
+        assert_eq!(
+            urls(&outlines),
+            vec![
+                "https://example.com/daily.xml?a=1&b=2",
+                "https://example.com/deep.xml",
+                "https://example.com/loud.xml",
+            ]
+        );
+        assert_eq!(outlines[1].title.as_deref(), Some("Deep"));
+        assert_eq!(outlines[2].website.as_deref(), Some("https://example.com/"));
+    }
+
+    #[test]
+    fn test_parse_rejects_other_documents() {
+        assert!(matches!(
+            parse_opml(b"<rss><channel/></rss>"),
+            Err(OpmlParseError::NotOpml)
+        ));
+    }
+
+    #[test]
+    fn test_write_escapes_and_round_trips() {
+        let outlines = vec![
+            OpmlOutline {
+                url: "https://example.com/feed.xml?a=1&b=\"2\"".to_string(),
+                title: Some("Tom & Jerry <Live>".to_string()),
+                website: Some("https://example.com/".to_string()),
+            },
+            OpmlOutline {
+                url: "https://example.com/untitled.xml".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let opml = write_opml("Subscriptions & more", &outlines);
+        assert!(opml.contains("a=1&amp;b=&quot;2&quot;"));
+        assert!(opml.contains("<title>Subscriptions &amp; more</title>"));
+
+        let parsed = parse_opml(opml.as_bytes()).unwrap();
+        assert_eq!(parsed[0], outlines[0]);
+        assert_eq!(
+            parsed[1].title.as_deref(),
+            Some("https://example.com/untitled.xml")
+        );
+    }
+}
@@ -0,0 +1,56 @@
+/// Maximum number of terms taken from a search query
+const MAX_TERMS: usize = 16;
+
+/// Builds an FTS5 `MATCH` expression from free-form user input.
+/// Every word becomes a quoted prefix query, so FTS5 operators and
+/// punctuation in the input cannot produce syntax errors.
+/// Returns `None` when the input contains no searchable words.
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_TERMS)
+        .map(|term| format!("\"{}\"*", term))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query_prefix_terms() {
+        assert_eq!(
+            fts_query("rust talk").as_deref(),
+            Some("\"rust\"* \"talk\"*")
+        );
+    }
+
+    #[test]
+    fn test_fts_query_strips_operators() {
+        assert_eq!(
+            fts_query("\"linux\" OR-(news*)").as_deref(),
+            Some("\"linux\"* \"OR\"* \"news\"*")
+        );
+    }
+
+    #[test]
+    fn test_fts_query_keeps_unicode_words() {
+        assert_eq!(
+            fts_query("Größe café").as_deref(),
+            Some("\"Größe\"* \"café\"*")
+        );
+    }
+
+    #[test]
+    fn test_fts_query_empty() {
+        assert_eq!(fts_query(""), None);
+        assert_eq!(fts_query("  %* \" "), None);
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, NaiveDateTime};
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// Parses the ISO 8601 timestamps of the gpodder API, e.g.
+/// `2009-12-12T09:00:00`. Timestamps without UTC offset are UTC;
+/// fractional seconds and Unix timestamps as strings are accepted as well.
+pub fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return Some(timestamp);
+    }
+
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .map(|dt| dt.and_utc().timestamp())
+        .or_else(|_| DateTime::parse_from_rfc3339(value).map(|dt| dt.timestamp()))
+        .ok()
+}
+
+/// Deserializes a timestamp given either as Unix timestamp or ISO 8601 string
+pub fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawTimestamp {
+        Unix(i64),
+        Text(String),
+    }
+
+    match RawTimestamp::deserialize(deserializer)? {
+        RawTimestamp::Unix(timestamp) => Ok(timestamp),
+        RawTimestamp::Text(text) => parse_timestamp(&text)
+            .ok_or_else(|| D::Error::custom(format!("invalid timestamp '{}'", text))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp_iso() {
+        assert_eq!(parse_timestamp("2009-12-12T09:00:00"), Some(1260608400));
+        assert_eq!(parse_timestamp("2009-12-12T09:00:00.250"), Some(1260608400));
+        assert_eq!(
+            parse_timestamp("2009-12-12T10:00:00+01:00"),
+            Some(1260608400)
+        );
+        assert_eq!(parse_timestamp("2009-12-12T09:00:00Z"), Some(1260608400));
+    }
+
+    #[test]
+    fn test_parse_timestamp_unix() {
+        assert_eq!(parse_timestamp("1260608400"), Some(1260608400));
+    }
+
+    #[test]
+    fn test_parse_timestamp_invalid() {
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("2009-12-12"), None);
+    }
+
+    #[test]
+    fn test_deserialize_timestamp_both_forms() {
+        #[derive(Deserialize)]
+        struct Action {
+            #[serde(deserialize_with = "deserialize_timestamp")]
+            timestamp: i64,
+        }
+
+        let unix: Action = serde_json::from_str(r#"{"timestamp": 1260608400}"#).unwrap();
+        let iso: Action = serde_json::from_str(r#"{"timestamp": "2009-12-12T09:00:00"}"#).unwrap();
+        assert_eq!(unix.timestamp, 1260608400);
+        assert_eq!(iso.timestamp, 1260608400);
+
+        assert!(serde_json::from_str::<Action>(r#"{"timestamp": "soon"}"#).is_err());
+    }
+}
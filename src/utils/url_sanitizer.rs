@@ -1,16 +1,77 @@
-/// Sanitizes a URL by validating it's HTTP or HTTPS protocol.
-/// Returns empty string for invalid URLs.
-pub fn sanitize_url(url: &str) -> String {
+use url::Url;
+
+/// Podcast-specific schemes that stand for a plain HTTP feed URL
+const FEED_SCHEMES: [&str; 4] = ["feed", "itpc", "pcast", "podcast"];
+
+/// Maps podcast-specific schemes to `http`: `feed://host/x` and
+/// `itpc://host/x` become `http://host/x`, and `feed:https://host/x` is
+/// unwrapped to `https://host/x`
+fn map_scheme(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once(':') else {
+        return url.to_string();
+    };
+    if !FEED_SCHEMES.iter().any(|s| s.eq_ignore_ascii_case(scheme)) {
+        return url.to_string();
+    }
+
+    match rest.strip_prefix("//") {
+        Some(host_and_path) => format!("http://{}", host_and_path),
+        None => rest.to_string(),
+    }
+}
+
+/// Whether a query parameter matches one of the configured tracking
+/// parameters; a trailing `*` matches any suffix (e.g. `utm_*`)
+fn is_tracking_param(key: &str, tracking_params: &[String]) -> bool {
+    tracking_params
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => key
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+            None => key.eq_ignore_ascii_case(pattern),
+        })
+}
+
+/// Normalizes a URL to its canonical form: trims whitespace, maps
+/// `feed://`, `itpc://`, `pcast://` and `podcast://` to `http://`, lowercases
+/// scheme and host, drops default ports, the fragment and the given tracking
+/// query parameters.
+/// Returns empty string for invalid or non-HTTP(S) URLs.
+pub fn sanitize_url(url: &str, tracking_params: &[String]) -> String {
+    let url = url.trim();
     if url.is_empty() {
         return String::new();
     }
 
-    // Check if URL starts with http:// or https://
-    if url.starts_with("http://") || url.starts_with("https://") {
-        url.to_string()
-    } else {
-        String::new()
+    let Ok(mut parsed) = Url::parse(&map_scheme(url)) else {
+        return String::new();
+    };
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return String::new();
+    }
+
+    parsed.set_fragment(None);
+
+    // Filtered on the raw query so the remaining parameters keep their
+    // original encoding
+    if let Some(query) = parsed.query().map(str::to_string) {
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .filter(|pair| {
+                let key = pair.split_once('=').map_or(*pair, |(key, _)| key);
+                !is_tracking_param(key, tracking_params)
+            })
+            .collect();
+        if kept.is_empty() {
+            parsed.set_query(None);
+        } else {
+            parsed.set_query(Some(&kept.join("&")));
+        }
     }
+
+    parsed.to_string()
 }
 
 /// Sanitizes a list of URLs and returns both the sanitized URLs
@@ -18,14 +79,17 @@ pub fn sanitize_url(url: &str) -> String {
 ///
 /// Returns: (sanitized_urls, update_urls)
 /// where update_urls is an array of [original, sanitized] pairs
-pub fn sanitize_urls(urls: &[String]) -> (Vec<String>, Vec<[String; 2]>) {
+pub fn sanitize_urls(
+    urls: &[String],
+    tracking_params: &[String],
+) -> (Vec<String>, Vec<[String; 2]>) {
     let mut sanitized = Vec::new();
     let mut updates = Vec::new();
 
     for url in urls {
-        let sanitized_url = sanitize_url(url);
+        let sanitized_url = sanitize_url(url, tracking_params);
 
-        // Track URLs that were changed (rewritten to empty string)
+        // Track URLs that were rewritten (normalized or blanked out)
         if url != &sanitized_url {
             updates.push([url.clone(), sanitized_url.clone()]);
         }
@@ -40,10 +104,14 @@ pub fn sanitize_urls(urls: &[String]) -> (Vec<String>, Vec<[String; 2]>) {
 mod tests {
     use super::*;
 
+    fn sanitize(url: &str) -> String {
+        sanitize_url(url, &["utm_*".to_string(), "fbclid".to_string()])
+    }
+
     #[test]
     fn test_sanitize_url_valid_http() {
         assert_eq!(
-            sanitize_url("http://example.com/feed.rss"),
+            sanitize("http://example.com/feed.rss"),
             "http://example.com/feed.rss"
         );
     }
@@ -51,24 +119,24 @@ mod tests {
     #[test]
     fn test_sanitize_url_valid_https() {
         assert_eq!(
-            sanitize_url("https://example.com/feed.rss"),
+            sanitize("https://example.com/feed.rss"),
             "https://example.com/feed.rss"
         );
     }
 
     #[test]
     fn test_sanitize_url_invalid_ftp() {
-        assert_eq!(sanitize_url("ftp://example.com/file"), "");
+        assert_eq!(sanitize("ftp://example.com/file"), "");
     }
 
     #[test]
     fn test_sanitize_url_invalid_javascript() {
-        assert_eq!(sanitize_url("javascript:alert(1)"), "");
+        assert_eq!(sanitize("javascript:alert(1)"), "");
     }
 
     #[test]
     fn test_sanitize_url_empty() {
-        assert_eq!(sanitize_url(""), "");
+        assert_eq!(sanitize(""), "");
     }
 
     #[test]
@@ -79,7 +147,7 @@ mod tests {
             "https://example.com/2".to_string(),
         ];
 
-        let (sanitized, updates) = sanitize_urls(&urls);
+        let (sanitized, updates) = sanitize_urls(&urls, &[]);
 
         assert_eq!(sanitized.len(), 3);
         assert_eq!(sanitized[0], "http://example.com/1");
@@ -89,4 +157,72 @@ mod tests {
         assert_eq!(updates.len(), 1);
         assert_eq!(updates[0], ["ftp://bad.com".to_string(), "".to_string()]);
     }
+
+    #[test]
+    fn test_sanitize_url_trims_and_lowercases_host() {
+        assert_eq!(
+            sanitize("  HTTP://Example.COM/Feed.xml \n"),
+            "http://example.com/Feed.xml"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_url_maps_feed_schemes() {
+        assert_eq!(
+            sanitize("feed://example.com/feed.xml"),
+            "http://example.com/feed.xml"
+        );
+        assert_eq!(
+            sanitize("feed:https://example.com/feed.xml"),
+            "https://example.com/feed.xml"
+        );
+        assert_eq!(
+            sanitize("itpc://example.com/feed.xml"),
+            "http://example.com/feed.xml"
+        );
+        assert_eq!(
+            sanitize("PCAST://example.com/feed.xml"),
+            "http://example.com/feed.xml"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_url_drops_default_port_and_fragment() {
+        assert_eq!(
+            sanitize("https://example.com:443/feed.xml#top"),
+            "https://example.com/feed.xml"
+        );
+        assert_eq!(
+            sanitize("http://example.com:8080/feed.xml"),
+            "http://example.com:8080/feed.xml"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_url_strips_tracking_params() {
+        assert_eq!(
+            sanitize("https://example.com/feed.xml?utm_source=x&UTM_medium=y&fbclid=z"),
+            "https://example.com/feed.xml"
+        );
+        assert_eq!(
+            sanitize("https://example.com/feed.xml?utm_source=x&token=a%2Bb&page=2"),
+            "https://example.com/feed.xml?token=a%2Bb&page=2"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_urls_reports_normalized_urls() {
+        let urls = vec!["https://Example.com/feed.xml".to_string()];
+
+        let (sanitized, updates) = sanitize_urls(&urls, &[]);
+
+        assert_eq!(sanitized, vec!["https://example.com/feed.xml".to_string()]);
+        assert_eq!(
+            updates,
+            vec![[
+                "https://Example.com/feed.xml".to_string(),
+                "https://example.com/feed.xml".to_string()
+            ]]
+        );
+    }
 }
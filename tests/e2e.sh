@@ -385,6 +385,30 @@ test_episode_actions_types() {
     assert_http_code "$status" "200" "Upload new action"
 }
 
+test_episode_actions_aggregated() {
+    log_info "Testing Episode Actions API - Aggregated Actions"
+
+    # Two position updates for the same episode, only the latest must be returned
+    local actions='[{"podcast":"http://example.com/feed1.rss","episode":"http://example.com/episode4.mp3","device":"'"$TEST_DEVICE"'","action":"play","started":0,"position":60,"total":500,"timestamp":1000},{"podcast":"http://example.com/feed1.rss","episode":"http://example.com/episode4.mp3","device":"'"$TEST_DEVICE"'","action":"play","started":60,"position":240,"total":500,"timestamp":2000}]'
+    http_request POST "/api/2/episodes/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" "$actions" >/dev/null
+
+    local response
+    response=$(http_request GET "/api/2/episodes/$TEST_USER/.json?aggregated=true" "$TEST_USER:$TEST_PASS")
+    local body=$(echo "$response" | head -n -1)
+    local status=$(echo "$response" | tail -n 1)
+
+    assert_http_code "$status" "200" "Get aggregated episode actions"
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '[.actions[] | select(.episode == "http://example.com/episode4.mp3")] | length == 1 and .[0].position == 240' >/dev/null 2>&1; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Aggregated actions contain only the latest action per episode"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Aggregated actions contain stale actions"
+    fi
+}
+
 # Test: Settings API
 test_settings_save() {
     echo
@@ -562,7 +586,7 @@ test_device_sync_status() {
     log_info "Testing Device Synchronization API - Get Status"
 
     local response
-    response=$(http_request GET "/api/2/sync-devices/$TEST_USER.json" "$TEST_USER:$TEST_PASS")
+    response=$(http_request GET "/api/2/sync-devices/$TEST_USER/.json" "$TEST_USER:$TEST_PASS")
     local body=$(echo "$response" | head -n -1)
     local status=$(echo "$response" | tail -n 1)
 
@@ -594,7 +618,7 @@ test_device_sync_create() {
     # Synchronize the two devices
     local sync_data="{\"synchronize\":[[\"$TEST_DEVICE\",\"$TEST_DEVICE2\"]]}"
     local response
-    response=$(http_request POST "/api/2/sync-devices/$TEST_USER.json" "$TEST_USER:$TEST_PASS" "$sync_data")
+    response=$(http_request POST "/api/2/sync-devices/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" "$sync_data")
     local body=$(echo "$response" | head -n -1)
     local status=$(echo "$response" | tail -n 1)
 
@@ -613,13 +637,39 @@ test_device_sync_create() {
     fi
 }
 
+test_device_sync_subscriptions() {
+    log_info "Testing Device Synchronization API - Subscription Propagation"
+
+    # Subscribe on the first device of the sync group
+    local podcast_url="http://example.com/synced-feed.rss"
+    local changes="{\"add\":[\"$podcast_url\"],\"remove\":[]}"
+    http_request POST "/api/2/subscriptions/$TEST_USER/$TEST_DEVICE/.json" "$TEST_USER:$TEST_PASS" "$changes" >/dev/null
+
+    # The second device must see the subscription as well
+    local response
+    response=$(http_request GET "/api/2/subscriptions/$TEST_USER/$TEST_DEVICE2/.json" "$TEST_USER:$TEST_PASS")
+    local body=$(echo "$response" | head -n -1)
+    local status=$(echo "$response" | tail -n 1)
+
+    assert_http_code "$status" "200" "Get subscriptions of synchronized device"
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --arg url "$podcast_url" 'index($url) != null' >/dev/null 2>&1; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Subscription propagated to synchronized device"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Subscription not propagated to synchronized device"
+    fi
+}
+
 test_device_sync_stop() {
     log_info "Testing Device Synchronization API - Stop Sync"
 
     # Stop syncing one device
     local sync_data="{\"stop-synchronize\":[\"$TEST_DEVICE2\"]}"
     local response
-    response=$(http_request POST "/api/2/sync-devices/$TEST_USER.json" "$TEST_USER:$TEST_PASS" "$sync_data")
+    response=$(http_request POST "/api/2/sync-devices/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" "$sync_data")
     local body=$(echo "$response" | head -n -1)
     local status=$(echo "$response" | tail -n 1)
 
@@ -824,6 +874,839 @@ test_session_cookie_logout() {
     rm -f /tmp/podsynq-cookies.txt
 }
 
+# Test: Admin web UI
+test_admin_ui_login() {
+    echo
+    log_info "Testing Admin Web UI - Login"
+
+    local status
+    status=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/admin/login")
+    assert_http_code "$status" "200" "Get admin login page"
+
+    status=$(curl -s -o /dev/null -w "%{http_code}" -c /tmp/podsynq-admin-cookies.txt \
+        --data-urlencode "username=$TEST_USER" --data-urlencode "password=$TEST_PASS" \
+        "$BASE_URL/admin/login")
+    assert_http_code "$status" "303" "Admin login redirects to dashboard"
+
+    status=$(curl -s -o /dev/null -w "%{http_code}" -b /tmp/podsynq-admin-cookies.txt "$BASE_URL/admin")
+    assert_http_code "$status" "200" "Get admin dashboard with session"
+
+    # A password reset from the web UI ends the user's existing logins
+    local user_id
+    http_request POST "/api/admin/users" "$TEST_USER:$TEST_PASS" '{"username":"e2e-ui-reset","password":"old-secret"}' > /dev/null
+    curl -s -o /dev/null -c /tmp/podsynq-reset-cookies.txt -X POST -u "e2e-ui-reset:old-secret" \
+        -H "Content-Type: application/json" -d "{}" "$BASE_URL/api/2/auth/e2e-ui-reset/login.json"
+    user_id=$(curl -s -b /tmp/podsynq-admin-cookies.txt "$BASE_URL/admin" \
+        | sed -n 's|.*href="/admin/users/\([0-9]*\)">e2e-ui-reset<.*|\1|p')
+
+    status=$(curl -s -o /dev/null -w "%{http_code}" -b /tmp/podsynq-admin-cookies.txt \
+        --data-urlencode "password=new-secret" "$BASE_URL/admin/users/$user_id/password")
+    assert_http_code "$status" "303" "Reset password from the web UI"
+
+    status=$(curl -s -o /dev/null -w "%{http_code}" -b /tmp/podsynq-reset-cookies.txt \
+        "$BASE_URL/api/2/devices/e2e-ui-reset.json")
+    assert_http_code "$status" "401" "Session from before the reset is rejected"
+
+    http_request DELETE "/api/admin/users/e2e-ui-reset" "$TEST_USER:$TEST_PASS" > /dev/null
+    rm -f /tmp/podsynq-admin-cookies.txt /tmp/podsynq-reset-cookies.txt
+}
+
+test_admin_user_management() {
+    echo
+    log_info "Testing Admin API - User Management"
+
+    local response status
+    response=$(http_request GET "/api/admin/users" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "List users as admin"
+
+    response=$(http_request POST "/api/admin/users" "$TEST_USER:$TEST_PASS" '{"username":"e2e-member","password":"secret"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "201" "Create user"
+
+    response=$(http_request GET "/api/admin/users" "e2e-member:secret")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "403" "List users as non-admin is forbidden"
+
+    response=$(http_request POST "/api/admin/users/e2e-member/disable" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Disable user"
+
+    response=$(http_request GET "/api/2/devices/e2e-member.json" "e2e-member:secret")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "401" "Disabled user cannot authenticate"
+
+    response=$(http_request DELETE "/api/admin/users/e2e-member" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Delete user"
+
+    # A disabled admin loses access to the web UI as well
+    http_request POST "/api/admin/users" "$TEST_USER:$TEST_PASS" '{"username":"e2e-admin2","password":"secret","is_admin":true}' > /dev/null
+    curl -s -o /dev/null -c /tmp/podsynq-admin2-cookies.txt \
+        --data-urlencode "username=e2e-admin2" --data-urlencode "password=secret" \
+        "$BASE_URL/admin/login"
+    http_request POST "/api/admin/users/e2e-admin2/disable" "$TEST_USER:$TEST_PASS" > /dev/null
+
+    status=$(curl -s -o /dev/null -w "%{http_code}" -b /tmp/podsynq-admin2-cookies.txt "$BASE_URL/admin")
+    assert_http_code "$status" "303" "Disabled admin is sent back to the web UI login"
+
+    curl -s -o /dev/null -b /tmp/podsynq-admin2-cookies.txt \
+        --data-urlencode "username=e2e-intruder" --data-urlencode "password=secret" \
+        "$BASE_URL/admin/users"
+    response=$(http_request GET "/api/admin/users/e2e-intruder" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "404" "Disabled admin cannot create users in the web UI"
+
+    http_request DELETE "/api/admin/users/e2e-admin2" "$TEST_USER:$TEST_PASS" > /dev/null
+    rm -f /tmp/podsynq-admin2-cookies.txt
+}
+
+test_account_self_service() {
+    echo
+    log_info "Testing Account API - Password Change and Deletion"
+
+    local response status
+    http_request POST "/api/admin/users" "$TEST_USER:$TEST_PASS" '{"username":"e2e-self","password":"old-secret"}' > /dev/null
+
+    response=$(http_request POST "/api/2/auth/e2e-self/password.json" "e2e-self:old-secret" '{"current_password":"wrong","new_password":"new-secret"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "401" "Change password with wrong current password"
+
+    response=$(http_request POST "/api/2/auth/e2e-self/password.json" "e2e-self:old-secret" '{"current_password":"old-secret","new_password":"new-secret"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Change password"
+
+    response=$(http_request GET "/api/2/devices/e2e-self.json" "e2e-self:old-secret")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "401" "Old password is rejected"
+
+    response=$(http_request POST "/api/2/auth/e2e-self/delete.json" "e2e-self:new-secret" '{"password":"new-secret"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Delete own account"
+
+    response=$(http_request GET "/api/2/devices/e2e-self.json" "e2e-self:new-secret")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "401" "Deleted account cannot authenticate"
+}
+
+test_directory_api() {
+    echo
+    log_info "Testing Directory API - Toplist, Search and Tags"
+
+    local response status
+    response=$(http_request GET "/toplist/10.json")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Get podcast toplist"
+
+    response=$(http_request GET "/toplist/many.json")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "400" "Reject invalid toplist size"
+
+    response=$(http_request GET "/search.json?q=podcast")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Search podcasts"
+
+    response=$(http_request GET "/search.opml?q=podcast")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Search podcasts as OPML"
+
+    response=$(http_request GET "/api/2/search.json?q=episode&scope=episodes")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Search episodes"
+
+    response=$(http_request GET "/api/2/search.json?q=podcast&scope=unknown")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "400" "Reject invalid search scope"
+
+    response=$(http_request GET "/api/2/tags/10.json")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Get top tags"
+
+    response=$(http_request GET "/api/2/data/podcast.json?url=http%3A%2F%2Funknown.example.com%2Ffeed.xml")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "404" "Unknown podcast returns 404"
+}
+
+test_suggestions() {
+    echo
+    log_info "Testing Suggestions API"
+
+    local response status
+    response=$(http_request GET "/suggestions/10.json" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Get suggestions as JSON"
+
+    response=$(http_request GET "/suggestions/10.opml" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Get suggestions as OPML"
+
+    response=$(http_request GET "/suggestions/10.json")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "401" "Suggestions require authentication"
+}
+
+test_gpoddersync_api() {
+    echo
+    log_info "Testing Nextcloud gPodder Sync API"
+
+    local prefix="/index.php/apps/gpoddersync"
+    local response body status
+
+    response=$(http_request POST "$prefix/subscription_change/create" "$TEST_USER:$TEST_PASS" '{"add":["https://example.com/gpoddersync.xml"],"remove":[]}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Upload gpoddersync subscription changes"
+
+    response=$(http_request GET "$prefix/subscriptions?since=0" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Get gpoddersync subscriptions"
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.add | index("https://example.com/gpoddersync.xml")' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Uploaded subscription is returned"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Uploaded subscription is missing"
+    fi
+
+    local actions='[{"podcast":"https://example.com/gpoddersync.xml","episode":"https://example.com/gpoddersync/1.mp3","guid":"gpoddersync-1","action":"PLAY","timestamp":"2024-01-02T03:04:05","started":0,"position":60,"total":600}]'
+    response=$(http_request POST "$prefix/episode_action/create" "$TEST_USER:$TEST_PASS" "$actions")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Upload gpoddersync episode actions"
+
+    response=$(http_request GET "$prefix/episode_action?since=0" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Get gpoddersync episode actions"
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.actions[] | select(.guid == "gpoddersync-1" and .action == "PLAY" and .timestamp == "2024-01-02T03:04:05")' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Episode action keeps guid and ISO timestamp"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Episode action guid or timestamp mismatch"
+    fi
+}
+
+test_episode_actions_validation() {
+    echo
+    log_info "Testing Episode API - ISO Timestamps and Validation"
+
+    local actions='[
+        {"podcast":"https://example.com/iso.xml","episode":"https://example.com/iso/1.mp3","device":"'"$TEST_DEVICE"'","action":"play","timestamp":"2009-12-12T09:00:00","started":0,"position":60,"total":600},
+        {"podcast":"https://example.com/iso.xml","episode":"https://example.com/iso/2.mp3","device":"'"$TEST_DEVICE"'","action":"download","timestamp":"2009-12-12T09:00:00","position":60},
+        {"podcast":"https://example.com/iso.xml","episode":"https://example.com/iso/3.mp3","device":"'"$TEST_DEVICE"'","action":"unknown","timestamp":1260608400}
+    ]'
+    local response body status
+    response=$(http_request POST "/api/2/episodes/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" "$actions")
+    body=$(echo "$response" | head -n -1)
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Upload episode actions with ISO timestamps"
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if [ "$(echo "$body" | jq -c '[.errors[].index]')" = "[1,2]" ]; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Invalid actions are reported per item"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected per-item errors: $body"
+    fi
+}
+
+test_episode_actions_idempotent() {
+    echo
+    log_info "Testing Episode API - Idempotent Uploads"
+
+    local actions='[{"podcast":"https://example.com/retry.xml","episode":"https://example.com/retry/1.mp3","device":"'"$TEST_DEVICE"'","action":"play","timestamp":1700000000,"started":0,"position":30,"total":300}]'
+    http_request POST "/api/2/episodes/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" "$actions" > /dev/null
+    http_request POST "/api/2/episodes/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" "$actions" > /dev/null
+
+    local response body count
+    response=$(http_request GET "/api/2/episodes/$TEST_USER/.json?podcast=https://example.com/retry.xml" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+    count=$(echo "$body" | jq '.actions | length')
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if [ "$count" = "1" ]; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Retried upload does not duplicate the action"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Expected 1 action after retry, got $count"
+    fi
+}
+
+# Actions recorded offline carry an old client timestamp, but must still
+# reach the next incremental sync
+test_episode_actions_cursor() {
+    echo
+    log_info "Testing Episode API - Sync Cursor"
+
+    local response body cursor
+    response=$(http_request GET "/api/2/episodes/$TEST_USER/.json" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+    cursor=$(echo "$body" | jq -r '.timestamp')
+
+    local actions='[{"podcast":"https://example.com/offline.xml","episode":"https://example.com/offline/1.mp3","device":"'"$TEST_DEVICE"'","action":"download","timestamp":1000}]'
+    http_request POST "/api/2/episodes/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" "$actions" > /dev/null
+
+    response=$(http_request GET "/api/2/episodes/$TEST_USER/.json?since=$cursor" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.actions[] | select(.episode == "https://example.com/offline/1.mp3")' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Action with old client timestamp is returned after the cursor"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Action with old client timestamp was skipped: $body"
+    fi
+
+    cursor=$(echo "$body" | jq -r '.timestamp')
+    response=$(http_request GET "/api/2/episodes/$TEST_USER/.json?since=$cursor" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if [ "$(echo "$body" | jq '.actions | length')" = "0" ]; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "No actions after the latest cursor"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Expected no actions after the latest cursor: $body"
+    fi
+}
+
+test_resubscribe() {
+    echo
+    log_info "Testing Subscriptions API - Resubscribe After Removal"
+
+    local url="https://example.com/resubscribe.xml"
+    local path="/api/2/subscriptions/$TEST_USER/$TEST_DEVICE/.json"
+    http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"add":["'"$url"'"]}' > /dev/null
+    http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"remove":["'"$url"'"]}' > /dev/null
+    http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"add":["'"$url"'"]}' > /dev/null
+
+    local response body
+    response=$(http_request GET "$path" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --arg url "$url" 'index($url) != null' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Podcast can be subscribed again after removal"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Resubscribed podcast missing: $body"
+    fi
+
+    response=$(http_request GET "/api/2/subscriptions/$TEST_USER/$TEST_DEVICE/history.json" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    local actions
+    actions=$(echo "$body" | jq -r --arg url "$url" '[.[] | select(.podcast == $url) | .action] | join(",")')
+    if [ "$actions" = "add,remove,add" ]; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Subscription history lists add, remove, add"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected subscription history: $actions"
+    fi
+}
+
+# A removal made offline before the podcast was (re-)added elsewhere loses
+test_subscription_conflict() {
+    echo
+    log_info "Testing Subscriptions API - Last Writer Wins"
+
+    local url="https://example.com/conflict.xml"
+    local path="/api/2/subscriptions/$TEST_USER/$TEST_DEVICE/.json"
+    local stale=$(( $(date +%s) - 3600 ))
+    http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"add":["'"$url"'"]}' > /dev/null
+
+    local response body
+    response=$(http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"remove":["'"$url"'"],"timestamp":'"$stale"'}')
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --arg url "$url" '.rejected[] | select(.podcast == $url and .action == "remove")' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Stale removal is reported as rejected"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Stale removal was not rejected: $body"
+    fi
+
+    response=$(http_request GET "$path" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --arg url "$url" 'index($url) != null' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Newer subscription is kept"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Newer subscription was overridden: $body"
+    fi
+}
+
+test_url_normalization() {
+    echo
+    log_info "Testing Subscriptions API - URL Normalization"
+
+    local path="/api/2/subscriptions/$TEST_USER/$TEST_DEVICE/.json"
+    local response body
+    response=$(http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"add":["feed://Normalize.Example.com:80/feed.xml?utm_source=app#latest"]}')
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.update_urls[] | select(.[1] == "http://normalize.example.com/feed.xml")' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Normalized URL is reported in update_urls"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Normalized URL missing from update_urls: $body"
+    fi
+
+    response=$(http_request GET "$path" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e 'index("http://normalize.example.com/feed.xml") != null' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Subscription is stored under the normalized URL"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Normalized subscription missing: $body"
+    fi
+}
+
+test_podcast_aliases() {
+    echo
+    log_info "Testing Admin API - Podcast Aliases"
+
+    local path="/api/2/subscriptions/$TEST_USER/$TEST_DEVICE/.json"
+    local response status body since
+    response=$(http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"add":["https://old.example.com/moved.xml"]}')
+    since=$(echo "$response" | head -n -1 | jq -r '.timestamp')
+
+    response=$(http_request POST "/api/admin/aliases" "$TEST_USER:$TEST_PASS" '{"alias":"https://old.example.com/moved.xml","podcast":"https://new.example.com/moved.xml"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "201" "Create podcast alias"
+
+    response=$(http_request GET "$path" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e 'index("https://new.example.com/moved.xml") != null and index("https://old.example.com/moved.xml") == null' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Existing subscription moved to the new URL"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Subscription was not moved: $body"
+    fi
+
+    response=$(http_request GET "$path?since=$since" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '(.add | index("https://new.example.com/moved.xml") != null) and (.remove | index("https://old.example.com/moved.xml") != null)' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Syncing clients see the move as a removal and an addition"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Move missing from subscription changes: $body"
+    fi
+
+    response=$(http_request POST "$path" "$TEST_USER:$TEST_PASS" '{"remove":["https://old.example.com/moved.xml"]}')
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.update_urls[] | select(.[0] == "https://old.example.com/moved.xml" and .[1] == "https://new.example.com/moved.xml")' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Uploads of the old URL are rewritten via update_urls"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Alias missing from update_urls: $body"
+    fi
+
+    response=$(http_request DELETE "/api/admin/aliases?url=https%3A%2F%2Fold.example.com%2Fmoved.xml" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Delete podcast alias"
+}
+
+test_opml_import_export() {
+    echo
+    log_info "Testing Simple API - OPML Import and Export"
+
+    local opml="<?xml version='1.0'?>
+<opml version='1.0'><head/><body>
+  <outline text='Folder'>
+    <outline type='rss' text='Nested'
+             xmlUrl='https://opml.example.com/feed?a=1&amp;b=2'/>
+  </outline>
+</body></opml>"
+    local response status body
+    response=$(http_request PUT "/subscriptions/$TEST_USER/e2e-opml/opml" "$TEST_USER:$TEST_PASS" "$opml" "text/xml")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Import nested OPML"
+
+    response=$(http_request GET "/subscriptions/$TEST_USER/e2e-opml/opml" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | grep -q 'xmlUrl="https://opml.example.com/feed?a=1&amp;b=2"'; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Nested feed is exported with an escaped URL"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Escaped feed URL missing from OPML export: $body"
+    fi
+
+    response=$(http_request PUT "/subscriptions/$TEST_USER/e2e-opml/opml" "$TEST_USER:$TEST_PASS" "not opml" "text/xml")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "400" "Reject invalid OPML"
+}
+
+test_simple_api_formats() {
+    echo
+    log_info "Testing Simple API - Output Formats"
+
+    local response status body
+    response=$(http_request GET "/subscriptions/$TEST_USER/e2e-opml/jsonp?jsonp=onLoad" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if [[ "$body" == onLoad\(*\) ]]; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "JSONP wraps the subscriptions in the callback"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected JSONP response: $body"
+    fi
+
+    response=$(http_request GET "/subscriptions/$TEST_USER/e2e-opml/xml" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | grep -q '<url>https://opml.example.com/feed?a=1&amp;b=2</url>'; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "XML lists the subscribed podcasts"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected XML response: $body"
+    fi
+
+    response=$(http_request GET "/subscriptions/$TEST_USER/e2e-opml/jsonp" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "400" "Reject JSONP without callback"
+
+    response=$(http_request GET "/subscriptions/$TEST_USER/yaml" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "400" "Reject unknown format"
+
+    response=$(http_request GET "/toplist/10.xml")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Toplist as XML"
+}
+
+test_podcast_lists() {
+    echo
+    log_info "Testing Podcast Lists API"
+
+    local response status body
+    http_request DELETE "/api/2/lists/$TEST_USER/list/e2e-list.json" "$TEST_USER:$TEST_PASS" > /dev/null
+
+    response=$(http_request POST "/api/2/lists/$TEST_USER/create.json?title=E2E%20List" "$TEST_USER:$TEST_PASS" '["https://lists.example.com/a.xml","https://lists.example.com/b.xml"]')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "303" "Create podcast list"
+
+    response=$(http_request POST "/api/2/lists/$TEST_USER/create.json?title=E2E%20List" "$TEST_USER:$TEST_PASS" '[]')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "409" "Reject duplicate list name"
+
+    response=$(http_request GET "/api/2/lists/$TEST_USER.json")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.[] | select(.name == "e2e-list" and .title == "E2E List")' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "List is published under its name"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "List missing from the user's lists: $body"
+    fi
+
+    response=$(http_request PUT "/api/2/lists/$TEST_USER/list/e2e-list.txt" "$TEST_USER:$TEST_PASS" "https://lists.example.com/c.xml" "text/plain")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "204" "Replace list podcasts"
+
+    response=$(http_request GET "/api/2/lists/$TEST_USER/list/e2e-list.txt")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if [ "$body" = "https://lists.example.com/c.xml" ]; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "List returns the replaced podcasts"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected list content: $body"
+    fi
+
+    response=$(http_request DELETE "/api/2/lists/$TEST_USER/list/e2e-list.json" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "204" "Delete podcast list"
+}
+
+test_chapters() {
+    echo
+    log_info "Testing Chapters API"
+
+    local response status body since
+    local episode="https://chapters.example.com/ep1.mp3"
+
+    response=$(http_request POST "/api/2/chapters/$TEST_USER.json" "$TEST_USER:$TEST_PASS" '{"podcast":"https://chapters.example.com/feed.xml","episode":"'"$episode"'","chapters_add":[{"start":0,"end":60,"label":"Intro"},{"start":60,"end":90,"label":"Sponsor","advertisement":true}]}')
+    status=$(echo "$response" | tail -n 1)
+    body=$(echo "$response" | head -n -1)
+    assert_http_code "$status" "200" "Upload chapters"
+    since=$(echo "$body" | jq -r '.timestamp')
+
+    response=$(http_request POST "/api/2/chapters/$TEST_USER.json" "$TEST_USER:$TEST_PASS" '{"podcast":"https://chapters.example.com/feed.xml","episode":"'"$episode"'","chapters_add":[{"start":90,"end":60}]}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "400" "Reject chapter ending before its start"
+
+    response=$(http_request GET "/api/2/chapters/$TEST_USER.json?episode=$episode" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.chapters | length == 2 and .[1].advertisement == true' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Episode chapters are returned"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected chapters: $body"
+    fi
+
+    response=$(http_request POST "/api/2/chapters/$TEST_USER.json" "$TEST_USER:$TEST_PASS" '{"podcast":"https://chapters.example.com/feed.xml","episode":"'"$episode"'","chapters_add":[{"start":0,"end":60,"label":"Welcome"}],"chapters_remove":[{"start":60,"end":90}]}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Relabel and remove chapters"
+
+    response=$(http_request GET "/api/2/chapters/$TEST_USER.json?since=$since" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '(.chapters | length == 1 and .[0].label == "Welcome") and (.removed == [{"podcast":"https://chapters.example.com/feed.xml","episode":"https://chapters.example.com/ep1.mp3","start":60,"end":90}])' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Only changed and removed chapters are returned since the cursor"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected chapters since $since: $body"
+    fi
+}
+
+test_bookmarks() {
+    echo
+    log_info "Testing Bookmarks API"
+
+    local response status body id since
+    local podcast="https://bookmarks.example.com/feed.xml"
+    local episode="https://bookmarks.example.com/ep1.mp3"
+
+    response=$(http_request POST "/api/2/bookmarks/$TEST_USER.json" "$TEST_USER:$TEST_PASS" '{"podcast":"'"$podcast"'","episode":"'"$episode"'","position":125,"note":"Great quote"}')
+    status=$(echo "$response" | tail -n 1)
+    body=$(echo "$response" | head -n -1)
+    assert_http_code "$status" "201" "Create bookmark"
+    id=$(echo "$body" | jq -r '.id')
+
+    response=$(http_request GET "/api/2/bookmarks/$TEST_USER.json?podcast=$podcast" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+    since=$(echo "$body" | jq -r '.timestamp')
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --argjson id "$id" '.bookmarks | map(select(.id == $id and .position == 125)) | length == 1' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Bookmark is listed for its podcast"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Bookmark missing from podcast listing: $body"
+    fi
+
+    response=$(http_request PUT "/api/2/bookmarks/$TEST_USER/$id.json" "$TEST_USER:$TEST_PASS" '{"note":"Best quote"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Update bookmark note"
+
+    response=$(http_request GET "/api/2/settings/$TEST_USER/episode.json?podcast=$podcast&episode=$episode" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.bookmarks == [{"position":125,"note":"Best quote"}]' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Bookmarks are exposed as episode setting"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected episode settings: $body"
+    fi
+
+    response=$(http_request POST "/api/2/settings/$TEST_USER/episode.json?podcast=$podcast&episode=$episode" "$TEST_USER:$TEST_PASS" '{"set":{"bookmarks":[{"position":300,"note":"Outro"}]}}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Replace bookmarks through episode settings"
+
+    response=$(http_request POST "/api/2/settings/$TEST_USER/episode.json?episode=https://bookmarks.example.com/unknown.mp3" "$TEST_USER:$TEST_PASS" '{"set":{"bookmarks":[{"position":10}]}}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "400" "Reject bookmarks setting of an unknown episode without podcast"
+
+    response=$(http_request GET "/api/2/bookmarks/$TEST_USER.json?since=$since" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --argjson id "$id" '(.deleted | index($id)) != null and (.bookmarks | map(.position) == [300])' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Incremental sync reports new and deleted bookmarks"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected bookmarks since $since: $body"
+    fi
+
+    id=$(echo "$body" | jq -r '.bookmarks[0].id')
+    response=$(http_request DELETE "/api/2/bookmarks/$TEST_USER/$id.json" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "204" "Delete bookmark"
+
+    response=$(http_request DELETE "/api/2/bookmarks/$TEST_USER/$id.json" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "404" "Deleted bookmark is gone"
+}
+
+test_device_merge_and_delete() {
+    echo
+    log_info "Testing Device Merge and Delete"
+
+    local response status body
+    local old_device="e2e-merge-old" new_device="e2e-merge-new"
+
+    http_request POST "/api/2/devices/$TEST_USER/$old_device.json" "$TEST_USER:$TEST_PASS" '{"caption":"Old install","type":"mobile"}' > /dev/null
+    http_request POST "/api/2/devices/$TEST_USER/$new_device.json" "$TEST_USER:$TEST_PASS" '{"caption":"New install","type":"mobile"}' > /dev/null
+    http_request PUT "/subscriptions/$TEST_USER/$old_device/txt" "$TEST_USER:$TEST_PASS" "https://merge.example.com/old.xml" "text/plain" > /dev/null
+    http_request PUT "/subscriptions/$TEST_USER/$new_device/txt" "$TEST_USER:$TEST_PASS" "https://merge.example.com/new.xml" "text/plain" > /dev/null
+
+    response=$(http_request POST "/api/2/devices/$TEST_USER/$old_device/merge.json" "$TEST_USER:$TEST_PASS" '{"into":"'"$new_device"'"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Merge device into another"
+
+    response=$(http_request GET "/subscriptions/$TEST_USER/$new_device/txt" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | grep -q "old.xml" && echo "$body" | grep -q "new.xml"; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Merged device has the subscriptions of both"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected subscriptions after merge: $body"
+    fi
+
+    response=$(http_request DELETE "/api/2/devices/$TEST_USER/$new_device.json" "$TEST_USER:$TEST_PASS")
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "204" "Delete device"
+
+    response=$(http_request GET "/api/2/devices/$TEST_USER.json" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --arg old "$old_device" --arg new "$new_device" 'map(select(.id == $old or .id == $new)) | length == 0' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Merged and deleted devices are gone"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Devices still listed: $body"
+    fi
+}
+
+test_device_merge_sync_groups() {
+    echo
+    log_info "Testing Device Merge Across Sync Groups"
+
+    local response status body device
+    for device in e2e-group-a e2e-group-b e2e-group-c e2e-group-d; do
+        http_request POST "/api/2/devices/$TEST_USER/$device.json" "$TEST_USER:$TEST_PASS" '{"caption":"Group test","type":"mobile"}' > /dev/null
+    done
+    http_request PUT "/subscriptions/$TEST_USER/e2e-group-a/txt" "$TEST_USER:$TEST_PASS" "https://groups.example.com/a.xml" "text/plain" > /dev/null
+    http_request PUT "/subscriptions/$TEST_USER/e2e-group-d/txt" "$TEST_USER:$TEST_PASS" "https://groups.example.com/d.xml" "text/plain" > /dev/null
+    http_request POST "/api/2/sync-devices/$TEST_USER/.json" "$TEST_USER:$TEST_PASS" '{"synchronize":[["e2e-group-a","e2e-group-b"],["e2e-group-c","e2e-group-d"]]}' > /dev/null
+
+    response=$(http_request POST "/api/2/devices/$TEST_USER/e2e-group-c/merge.json" "$TEST_USER:$TEST_PASS" '{"into":"e2e-group-a"}')
+    status=$(echo "$response" | tail -n 1)
+    assert_http_code "$status" "200" "Merge devices of different sync groups"
+
+    response=$(http_request GET "/api/2/sync-devices/$TEST_USER/.json" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e '.synchronized | any(sort == ["e2e-group-a","e2e-group-b","e2e-group-d"])' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Sync groups of both devices are merged"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected sync groups after merge: $body"
+    fi
+
+    response=$(http_request GET "/subscriptions/$TEST_USER/e2e-group-b/txt" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | grep -q "a.xml" && echo "$body" | grep -q "d.xml"; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Devices of the merged group share their subscriptions"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Unexpected subscriptions after merging groups: $body"
+    fi
+}
+
+test_device_last_seen() {
+    echo
+    log_info "Testing Device Last Seen"
+
+    local response body
+    http_request GET "/subscriptions/$TEST_USER/$TEST_DEVICE/json" "$TEST_USER:$TEST_PASS" > /dev/null
+
+    response=$(http_request GET "/api/2/devices/$TEST_USER.json" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if echo "$body" | jq -e --arg id "$TEST_DEVICE" '.[] | select(.id == $id and .last_seen > 0 and (.user_agent | startswith("curl")))' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Device list shows last sync and user agent"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Device activity missing: $body"
+    fi
+
+    local before
+    http_request POST "/api/2/devices/$TEST_USER/e2e-seen-actions.json" "$TEST_USER:$TEST_PASS" '{"caption":"Actions only"}' > /dev/null
+    http_request POST "/api/2/devices/$TEST_USER/e2e-seen-history.json" "$TEST_USER:$TEST_PASS" '{"caption":"History only"}' > /dev/null
+    before=$(http_request GET "/api/2/devices/$TEST_USER.json" "$TEST_USER:$TEST_PASS" | head -n -1)
+
+    # last_seen has a resolution of one second
+    sleep 1
+    http_request GET "/api/2/episodes/$TEST_USER/.json?device=e2e-seen-actions" "$TEST_USER:$TEST_PASS" > /dev/null
+    http_request GET "/api/2/subscriptions/$TEST_USER/e2e-seen-history/history.json" "$TEST_USER:$TEST_PASS" > /dev/null
+
+    response=$(http_request GET "/api/2/devices/$TEST_USER.json" "$TEST_USER:$TEST_PASS")
+    body=$(echo "$response" | head -n -1)
+
+    TESTS_RUN=$((TESTS_RUN + 1))
+    if jq -e -n --argjson before "$before" --argjson after "$body" '["e2e-seen-actions", "e2e-seen-history"] | all(. as $id | ($after[] | select(.id == $id) | .last_seen) > ($before[] | select(.id == $id) | .last_seen))' > /dev/null; then
+        TESTS_PASSED=$((TESTS_PASSED + 1))
+        log_success "Episode action and history downloads count as syncs"
+    else
+        TESTS_FAILED=$((TESTS_FAILED + 1))
+        log_error "Device activity missing after downloads: $body"
+    fi
+}
+
 # Print summary
 print_summary() {
     echo
@@ -879,6 +1762,7 @@ main() {
     test_episode_actions_upload
     test_episode_actions_get
     test_episode_actions_types
+    test_episode_actions_aggregated
     
     test_settings_save
     test_settings_get
@@ -892,6 +1776,7 @@ main() {
 
     test_device_sync_status
     test_device_sync_create
+    test_device_sync_subscriptions
     test_device_sync_stop
 
     test_favorites_via_settings
@@ -904,6 +1789,28 @@ main() {
     test_session_cookie_request
     test_session_cookie_logout
 
+    test_admin_ui_login
+    test_admin_user_management
+    test_account_self_service
+    test_directory_api
+    test_suggestions
+    test_gpoddersync_api
+    test_episode_actions_validation
+    test_episode_actions_idempotent
+    test_episode_actions_cursor
+    test_resubscribe
+    test_subscription_conflict
+    test_url_normalization
+    test_podcast_aliases
+    test_opml_import_export
+    test_simple_api_formats
+    test_podcast_lists
+    test_chapters
+    test_bookmarks
+    test_device_merge_and_delete
+    test_device_merge_sync_groups
+    test_device_last_seen
+
     # Print summary
     print_summary
 }